@@ -0,0 +1,260 @@
+//! AWS Bedrock support through the Converse API.
+//!
+//! Enabled with the `bedrock` feature. `RequestBuilder` renders requests for
+//! `ClientLlm::Bedrock` in the Converse format, and `BedrockClient` signs them with AWS
+//! Signature Version 4 before sending them to the `bedrock-runtime` endpoint of the
+//! configured region.
+//!
+//! ```no_run
+//! # use llm_bridge::bedrock::BedrockClient;
+//! # use llm_bridge::client::LlmClient;
+//! let client = BedrockClient::new("us-west-2", "AKIA...", "secret", None);
+//! let client = LlmClient::bedrock(client);
+//! let request = client.request()
+//!     .model("anthropic.claude-3-5-sonnet-20240620-v1:0")
+//!     .user_message("Hello!");
+//! ```
+
+use std::env;
+use std::time::SystemTime;
+use aws_credential_types::Credentials;
+use aws_sigv4::http_request::{sign, SignableBody, SignableRequest, SigningSettings};
+use aws_sigv4::sign::v4;
+use log::debug;
+use reqwest::Client;
+use crate::client::{redact, response_meta, send_with_retries, with_custom_headers, ClientLlm, LlmClientTrait};
+use crate::error::ApiError;
+use crate::request::RequestOptions;
+use crate::response::{BedrockResponse, ResponseMessage};
+
+const DEFAULT_REGION: &str = "us-east-1";
+const SIGNING_NAME: &str = "bedrock";
+
+/// Wrapper around the AWS Bedrock runtime API client.
+pub struct BedrockClient {
+    region: String,
+    base_url: String,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    max_retries: u32,
+    client: Client,
+}
+
+impl BedrockClient {
+    /// Creates a client for `region` that signs requests with the given AWS credentials.
+    pub fn new(region: &str, access_key_id: &str, secret_access_key: &str, session_token: Option<&str>) -> Self {
+        BedrockClient {
+            region: region.to_string(),
+            base_url: format!("https://bedrock-runtime.{}.amazonaws.com", region),
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+            session_token: session_token.map(str::to_string),
+            max_retries: 0,
+            client: Client::new(),
+        }
+    }
+
+    /// Creates a client from the standard AWS environment variables: `AWS_ACCESS_KEY_ID`,
+    /// `AWS_SECRET_ACCESS_KEY`, the optional `AWS_SESSION_TOKEN`, and `AWS_REGION` (falling
+    /// back to `AWS_DEFAULT_REGION`, then `us-east-1`).
+    pub fn from_env() -> Self {
+        let region = env::var("AWS_REGION")
+            .or_else(|_| env::var("AWS_DEFAULT_REGION"))
+            .unwrap_or_else(|_| DEFAULT_REGION.to_string());
+        BedrockClient::new(
+            &region,
+            &env::var("AWS_ACCESS_KEY_ID").unwrap_or_default(),
+            &env::var("AWS_SECRET_ACCESS_KEY").unwrap_or_default(),
+            env::var("AWS_SESSION_TOKEN").ok().as_deref(),
+        )
+    }
+
+    /// Sets how many times a request is retried after a connection failure, timeout, throttling
+    /// (429) or server error.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Overrides the regional runtime endpoint, e.g. for a VPC interface endpoint.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Returns the SigV4 headers for a POST of `body` to `url`.
+    fn signed_headers(&self, url: &str, body: &[u8]) -> Result<Vec<(String, String)>, ApiError> {
+        let signing_error = |e: &dyn std::fmt::Display| ApiError::InvalidUsage(format!("Failed to sign Bedrock request: {}", e));
+        let identity = Credentials::new(
+            &self.access_key_id,
+            &self.secret_access_key,
+            self.session_token.clone(),
+            None,
+            "llm-bridge",
+        ).into();
+        let params = v4::SigningParams::builder()
+            .identity(&identity)
+            .region(&self.region)
+            .name(SIGNING_NAME)
+            .time(SystemTime::now())
+            .settings(SigningSettings::default())
+            .build()
+            .map_err(|e| signing_error(&e))?
+            .into();
+        let signable = SignableRequest::new(
+            "POST",
+            url,
+            [("content-type", "application/json")].into_iter(),
+            SignableBody::Bytes(body),
+        ).map_err(|e| signing_error(&e))?;
+        let (instructions, _signature) = sign(signable, &params).map_err(|e| signing_error(&e))?.into_parts();
+        Ok(instructions.headers()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect())
+    }
+}
+
+/// Percent-encodes a model id or ARN for use as a single URL path segment.
+fn encode_path_segment(segment: &str) -> String {
+    segment.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl LlmClientTrait for BedrockClient {
+    async fn send_message(
+        &self,
+        request_body: serde_json::Value,
+        options: &RequestOptions,
+    ) -> Result<ResponseMessage, ApiError> {
+        let response = self.send_raw_response(request_body, options).await?;
+        let meta = response_meta(&response, ClientLlm::Bedrock);
+        let resp_status = response.status();
+        let resp_text = response.text().await.unwrap_or("".into());
+        let resp_text = redact(&resp_text, &[&self.secret_access_key]);
+        if resp_status.is_client_error() {
+            return Err(ApiError::ClientError(format!("Status: {} - Error: {}", resp_status, resp_text)));
+        } else if resp_status.is_server_error() {
+            return Err(ApiError::ServerError(format!("Status: {} - Error: {}", resp_status, resp_text)));
+        }
+        debug!("LLM call response: status[{}]\n{}", resp_status, resp_text);
+        let bedrock_response: BedrockResponse = serde_json::from_str(&resp_text)?;
+        Ok(ResponseMessage::Bedrock(bedrock_response).with_raw_body(resp_text).with_meta(meta))
+    }
+
+    fn client_type(&self) -> ClientLlm {
+        ClientLlm::Bedrock
+    }
+
+    async fn send_raw_response(
+        &self,
+        request_body: serde_json::Value,
+        options: &RequestOptions,
+    ) -> Result<reqwest::Response, ApiError> {
+        send_with_retries(self.http_request(request_body, options)?, self.max_retries).await
+    }
+
+    fn build_http_request(
+        &self,
+        request_body: serde_json::Value,
+        options: &RequestOptions,
+    ) -> Result<reqwest::Request, ApiError> {
+        Ok(self.http_request(request_body, options)?.build()?)
+    }
+}
+
+impl BedrockClient {
+    /// Builds the signed Converse request for `request_body`.
+    fn http_request(
+        &self,
+        mut request_body: serde_json::Value,
+        options: &RequestOptions,
+    ) -> Result<reqwest::RequestBuilder, ApiError> {
+        let model = request_body.as_object_mut()
+            .and_then(|body| body.remove("model"))
+            .and_then(|model| model.as_str().map(str::to_string))
+            .ok_or_else(|| ApiError::InvalidUsage("Bedrock requests require a model".to_string()))?;
+        let url = format!("{}/model/{}/converse", self.base_url, encode_path_segment(&model));
+        let body = serde_json::to_vec(&request_body)?;
+
+        let mut request = self.client.post(&url);
+        request = with_custom_headers(request, options,
+                                      &["authorization", "content-type", "x-amz-date", "x-amz-security-token"]);
+        for (name, value) in self.signed_headers(&url, &body)? {
+            request = request.header(name, value);
+        }
+        Ok(request
+            .header("content-type", "application/json")
+            .body(body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::RequestBuilder;
+    use serde_json::json;
+    use wiremock::matchers::{header, header_exists, header_regex, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_encode_path_segment() {
+        assert_eq!(encode_path_segment("anthropic.claude-3-haiku-20240307-v1:0"),
+                   "anthropic.claude-3-haiku-20240307-v1%3A0");
+        assert_eq!(encode_path_segment("arn:aws:bedrock:us-east-1::foundation-model/x"),
+                   "arn%3Aaws%3Abedrock%3Aus-east-1%3A%3Afoundation-model%2Fx");
+    }
+
+    #[tokio::test]
+    async fn test_bedrock_send_message_signed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/model/anthropic.claude-3-haiku-20240307-v1%3A0/converse"))
+            .and(header_regex("authorization", r"^AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/\d{8}/us-west-2/bedrock/aws4_request"))
+            .and(header_exists("x-amz-date"))
+            .and(header("x-amz-security-token", "session-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "output": {"message": {"role": "assistant", "content": [{"text": "Hello!"}]}},
+                "stopReason": "end_turn",
+                "usage": {"inputTokens": 8, "outputTokens": 3, "totalTokens": 11}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = BedrockClient::new("us-west-2", "AKIDEXAMPLE", "secret", Some("session-token"))
+            .with_base_url(&server.uri());
+
+        let response = RequestBuilder::new(&client)
+            .user_message("Hello!")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.first_message(), "Hello!");
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert!(body.get("model").is_none());
+        assert_eq!(body["messages"][0]["content"], json!([{"text": "Hello!"}]));
+    }
+
+    #[test]
+    fn test_bedrock_dry_run() {
+        let client = BedrockClient::new("us-west-2", "AKIDEXAMPLE", "secret", None);
+
+        let dry_run = RequestBuilder::new(&client)
+            .model("anthropic.claude-3-haiku-20240307-v1:0")
+            .user_message("Hello!")
+            .dry_run()
+            .unwrap();
+
+        assert_eq!(dry_run.url,
+                   "https://bedrock-runtime.us-west-2.amazonaws.com/model/anthropic.claude-3-haiku-20240307-v1%3A0/converse");
+        assert!(dry_run.headers.iter().any(|(name, value)| name == "authorization" && value == "[REDACTED]"));
+        assert!(dry_run.headers.iter().any(|(name, _)| name == "x-amz-date"));
+    }
+}
@@ -0,0 +1,190 @@
+//! A synchronous wrapper around `RequestBuilder` for code that doesn't run on an async runtime,
+//! such as CLI scripts and build tasks.
+//!
+//! Enabled with the `blocking` feature. Each `send` runs the request to completion on a private
+//! single-threaded Tokio runtime, so it must not be called from within an async context.
+//!
+//! ```no_run
+//! # use llm_bridge::client::{ClientLlm, LlmClient};
+//! let client = LlmClient::new(ClientLlm::Anthropic, "api-key".to_string());
+//! let response = client.blocking()
+//!     .user_message("Hello!")
+//!     .send()
+//!     .expect("request failed");
+//! println!("{}", response.first_message());
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::client::{LlmClient, RequestBuilder};
+use crate::error::ApiError;
+use crate::request::{DryRun, Message, ReasoningEffort, SystemRole};
+use crate::response::{ResponseMessage, ToolResponse};
+use crate::streaming::StreamRecovery;
+use crate::tool::Tool;
+
+/// Generates `BlockingRequestBuilder` setters that forward to the `RequestBuilder` method of the
+/// same name.
+macro_rules! forward_setters {
+    ($($name:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            #[doc = concat!("See [`RequestBuilder::", stringify!($name), "`].")]
+            pub fn $name(self, $($arg: $ty),*) -> Self {
+                BlockingRequestBuilder { inner: self.inner.$name($($arg),*) }
+            }
+        )*
+    };
+}
+
+/// A `RequestBuilder` whose `send` blocks the current thread until the response arrives.
+#[derive(Clone)]
+pub struct BlockingRequestBuilder<'a> {
+    inner: RequestBuilder<'a>,
+}
+
+impl<'a> From<RequestBuilder<'a>> for BlockingRequestBuilder<'a> {
+    fn from(inner: RequestBuilder<'a>) -> Self {
+        BlockingRequestBuilder { inner }
+    }
+}
+
+impl<'a> BlockingRequestBuilder<'a> {
+    forward_setters! {
+        add_tool(tool: Tool);
+        allow_tools(names: &[&str]);
+        model(model: &str);
+        user_message(message: &str);
+        assistant_message(message: &str);
+        messages(messages: Vec<Message>);
+        clear_messages();
+        tool_result(tool_use_id: &str, content: &str);
+        continue_with_tool_results(previous: &ResponseMessage, results: Vec<(ToolResponse, String)>);
+        api_key(api_key: &str);
+        header(name: &str, value: &str);
+        timeout(timeout: Duration);
+        stream_idle_timeout(timeout: Duration);
+        stream_recovery(recovery: StreamRecovery);
+        max_tokens(max_tokens: u32);
+        temperature(temperature: f64);
+        top_p(top_p: f64);
+        top_k(top_k: u32);
+        frequency_penalty(frequency_penalty: f64);
+        presence_penalty(presence_penalty: f64);
+        parallel_tool_calls(parallel_tool_calls: bool);
+        reasoning_effort(reasoning_effort: ReasoningEffort);
+        extra_body(extra_body: serde_json::Value);
+        seed(seed: u64);
+        n(n: u32);
+        logprobs(top_n: Option<u32>);
+        user_id(user_id: &str);
+        json_mode();
+        min_p(min_p: f64);
+        repeat_penalty(repeat_penalty: f64);
+        reasoning_model_prefixes(prefixes: &[&str]);
+        system_prompt(system_prompt: &str);
+        system_role(system_role: SystemRole);
+        system_prompt_value(system_prompt: serde_json::Value);
+    }
+
+    /// See [`RequestBuilder::add_tools`].
+    pub fn add_tools(self, tools: impl IntoIterator<Item = Tool>) -> Self {
+        BlockingRequestBuilder { inner: self.inner.add_tools(tools) }
+    }
+
+    /// See [`RequestBuilder::logit_bias`].
+    pub fn logit_bias<K: ToString>(self, logit_bias: HashMap<K, i32>) -> Self {
+        BlockingRequestBuilder { inner: self.inner.logit_bias(logit_bias) }
+    }
+
+    /// Renders the request body without sending it. See [`RequestBuilder::render_request`].
+    pub fn render_request(&self) -> Result<serde_json::Value, ApiError> {
+        self.inner.render_request()
+    }
+
+    /// See [`RequestBuilder::validate`].
+    pub fn validate(&self) -> Result<(), ApiError> {
+        self.inner.validate()
+    }
+
+    /// See [`RequestBuilder::dry_run`].
+    pub fn dry_run(&self) -> Result<DryRun, ApiError> {
+        self.inner.dry_run()
+    }
+
+    /// Returns the underlying async builder.
+    pub fn into_async(self) -> RequestBuilder<'a> {
+        self.inner
+    }
+
+    /// Sends the request, blocking until the response arrives.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within an async runtime.
+    pub fn send(self) -> Result<ResponseMessage, ApiError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        runtime.block_on(self.inner.send())
+    }
+
+    /// Streams the response, calling `on_delta` with each fragment of text as it arrives, and
+    /// returns the complete response once the stream ends.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within an async runtime.
+    pub fn send_streaming(self, on_delta: impl FnMut(&str)) -> Result<ResponseMessage, ApiError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        runtime.block_on(self.inner.send_streaming(on_delta))
+    }
+}
+
+impl LlmClient {
+    /// Creates a new `BlockingRequestBuilder` for sending a request without `.await`.
+    pub fn blocking(&self) -> BlockingRequestBuilder<'_> {
+        self.request().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_blocking_send() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = runtime.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/chat/completions"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "id": "chatcmpl-blocking",
+                    "object": "chat.completion",
+                    "created": 1721962302,
+                    "model": "llama3",
+                    "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hello!"}, "finish_reason": "stop"}],
+                    "usage": {"prompt_tokens": 5, "completion_tokens": 2, "total_tokens": 7}
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+            server
+        });
+        let client = LlmClient::openai_compatible(&server.uri(), None);
+
+        let response = client.blocking()
+            .model("llama3")
+            .user_message("Hello!")
+            .send()
+            .unwrap();
+
+        assert_eq!(response.first_message(), "Hello!");
+        runtime.block_on(server.verify());
+    }
+}
@@ -14,9 +14,24 @@ pub enum ApiError {
     #[error("Response parse error: {0}")]
     ResponseParseError(#[from] serde_json::Error),
 
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
     #[error("Missing required 'messages' parameter")]
     MissingMessages,
     
     #[error("Invalid API Usage: {0}")]
     InvalidUsage(String),
+
+    #[error("Request was cancelled")]
+    Cancelled,
+
+    #[error("Stream received no data for {0:?}")]
+    StreamIdleTimeout(std::time::Duration),
+
+    #[error("Request did not complete within {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("Response rejected by the model: {0}")]
+    Rejected(String),
 }
\ No newline at end of file
@@ -2,4 +2,18 @@ pub mod client;
 pub mod request;
 pub mod error;
 pub mod tool;
-pub mod response;
\ No newline at end of file
+pub mod response;
+pub mod registry;
+pub mod pricing;
+pub mod text;
+pub mod streaming;
+#[cfg(feature = "opentelemetry")]
+pub mod metrics;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "bedrock")]
+pub mod bedrock;
+#[cfg(feature = "axum")]
+pub mod sse;
+#[cfg(feature = "realtime")]
+pub mod realtime;
@@ -0,0 +1,236 @@
+//! OpenTelemetry metrics for LLM requests.
+//!
+//! Enabled with the `opentelemetry` feature. Every request sent through `RequestBuilder::send`,
+//! its `stream` variants or `send_raw_response` is recorded against the global meter provider,
+//! so applications only need to install their own `MeterProvider` (e.g. an OTLP exporter)
+//! before the first request for the metrics below to be exported:
+//!
+//! - `llm.requests` (counter): number of requests sent
+//! - `llm.errors` (counter): number of requests that returned an error
+//! - `llm.request.duration` (histogram, seconds): request latency
+//! - `llm.tokens` (counter): tokens consumed, split by the `token.type` attribute (`input`/`output`)
+//!
+//! All metrics carry `llm.provider`, `llm.model` and `llm.outcome` (`success`/`error`) attributes.
+//!
+//! Streams are recorded when they end, with the usage they reported and their full duration;
+//! streams dropped before then are not recorded. Raw responses have no token counts, and count
+//! as errors when their status is not a success.
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use futures_util::StreamExt;
+use opentelemetry::metrics::{Counter, Histogram, Meter};
+use opentelemetry::KeyValue;
+use crate::client::ClientLlm;
+use crate::error::ApiError;
+use crate::response::{CommonUsage, ResponseMessage};
+use crate::streaming::{EventStream, StreamEvent};
+
+const METER_NAME: &str = "llm-bridge";
+
+/// The set of instruments used to record LLM request metrics.
+pub struct LlmMetrics {
+    requests: Counter<u64>,
+    errors: Counter<u64>,
+    duration: Histogram<f64>,
+    tokens: Counter<u64>,
+}
+
+impl LlmMetrics {
+    /// Creates the instruments on the given meter.
+    pub fn new(meter: &Meter) -> Self {
+        LlmMetrics {
+            requests: meter
+                .u64_counter("llm.requests")
+                .with_description("Number of LLM requests sent")
+                .build(),
+            errors: meter
+                .u64_counter("llm.errors")
+                .with_description("Number of LLM requests that returned an error")
+                .build(),
+            duration: meter
+                .f64_histogram("llm.request.duration")
+                .with_description("Latency of LLM requests")
+                .with_unit("s")
+                .build(),
+            tokens: meter
+                .u64_counter("llm.tokens")
+                .with_description("Number of tokens consumed by LLM requests")
+                .build(),
+        }
+    }
+
+    /// Returns the instruments of the global meter provider, created on first use.
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<LlmMetrics> = OnceLock::new();
+        GLOBAL.get_or_init(|| Self::new(&opentelemetry::global::meter(METER_NAME)))
+    }
+
+    /// Records the outcome of a single request.
+    pub fn record(
+        &self,
+        provider: ClientLlm,
+        model: &str,
+        result: &Result<ResponseMessage, ApiError>,
+        elapsed: Duration,
+    ) {
+        let usage = result.as_ref().ok().map(ResponseMessage::usage);
+        self.record_usage(provider, model, result.is_ok(), usage.as_ref(), elapsed);
+    }
+
+    /// Records the outcome of a request whose response isn't a `ResponseMessage`, such as a
+    /// stream, with the usage it reported, if any.
+    pub fn record_usage(
+        &self,
+        provider: ClientLlm,
+        model: &str,
+        succeeded: bool,
+        usage: Option<&CommonUsage>,
+        elapsed: Duration,
+    ) {
+        let outcome = if succeeded { "success" } else { "error" };
+        let attributes = [
+            KeyValue::new("llm.provider", match provider {
+                ClientLlm::Custom(name) => name,
+                provider => format!("{:?}", provider).to_lowercase(),
+            }),
+            KeyValue::new("llm.model", model.to_string()),
+            KeyValue::new("llm.outcome", outcome),
+        ];
+
+        self.requests.add(1, &attributes);
+        self.duration.record(elapsed.as_secs_f64(), &attributes);
+        if !succeeded {
+            self.errors.add(1, &attributes);
+        }
+        if let Some(usage) = usage {
+            let mut input = attributes.to_vec();
+            input.push(KeyValue::new("token.type", "input"));
+            self.tokens.add(usage.input_tokens as u64, &input);
+            let mut output = attributes.to_vec();
+            output.push(KeyValue::new("token.type", "output"));
+            self.tokens.add(usage.output_tokens as u64, &output);
+        }
+    }
+}
+
+/// Wraps `events` to record the stream on the global instruments once it ends, timed from
+/// `start`.
+pub(crate) fn record_stream<'a>(
+    events: EventStream<'a>,
+    provider: ClientLlm,
+    model: String,
+    start: Instant,
+) -> EventStream<'a> {
+    Box::pin(async_stream::stream! {
+        let mut events = events;
+        let mut usage = None;
+        while let Some(event) = events.next().await {
+            match &event {
+                Ok(StreamEvent::Usage(reported)) => usage = Some(reported.clone()),
+                Err(_) => {
+                    LlmMetrics::global().record_usage(provider, &model, false, None, start.elapsed());
+                    yield event;
+                    return;
+                }
+                Ok(_) => {}
+            }
+            yield event;
+        }
+        LlmMetrics::global().record_usage(provider, &model, true, usage.as_ref(), start.elapsed());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData, ResourceMetrics};
+    use opentelemetry_sdk::metrics::{InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+    use serde_json::json;
+
+    fn anthropic_response() -> ResponseMessage {
+        serde_json::from_value(json!({
+            "id": "msg_01",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-haiku-20240307",
+            "content": [{"type": "text", "text": "Hello!"}],
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 12, "output_tokens": 30}
+        })).unwrap()
+    }
+
+    fn counter_total(metrics: &[ResourceMetrics], name: &str, filter: Option<(&str, &str)>) -> u64 {
+        metrics.iter()
+            .flat_map(|rm| rm.scope_metrics())
+            .flat_map(|sm| sm.metrics())
+            .filter(|metric| metric.name() == name)
+            .map(|metric| match metric.data() {
+                AggregatedMetrics::U64(MetricData::Sum(sum)) => sum.data_points()
+                    .filter(|point| filter.is_none_or(|(key, value)| point.attributes()
+                        .any(|kv| kv.key.as_str() == key && kv.value.as_str() == value)))
+                    .map(|point| point.value())
+                    .sum(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_metrics_recorded() {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .build();
+        let metrics = LlmMetrics::new(&provider.meter("test"));
+
+        metrics.record(ClientLlm::Anthropic, "claude-3-haiku-20240307",
+                       &Ok(anthropic_response()), Duration::from_millis(250));
+        metrics.record(ClientLlm::Anthropic, "claude-3-haiku-20240307",
+                       &Err(ApiError::ServerError("overloaded".to_string())), Duration::from_millis(50));
+
+        provider.force_flush().unwrap();
+        let finished = exporter.get_finished_metrics().unwrap();
+
+        assert_eq!(counter_total(&finished, "llm.requests", None), 2);
+        assert_eq!(counter_total(&finished, "llm.requests", Some(("llm.outcome", "success"))), 1);
+        assert_eq!(counter_total(&finished, "llm.errors", None), 1);
+        assert_eq!(counter_total(&finished, "llm.errors", Some(("llm.provider", "anthropic"))), 1);
+        assert_eq!(counter_total(&finished, "llm.tokens", Some(("token.type", "input"))), 12);
+        assert_eq!(counter_total(&finished, "llm.tokens", Some(("token.type", "output"))), 30);
+
+        let histogram_count: u64 = finished.iter()
+            .flat_map(|rm| rm.scope_metrics())
+            .flat_map(|sm| sm.metrics())
+            .filter(|metric| metric.name() == "llm.request.duration")
+            .map(|metric| match metric.data() {
+                AggregatedMetrics::F64(MetricData::Histogram(histogram)) =>
+                    histogram.data_points().map(|point| point.count()).sum(),
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(histogram_count, 2);
+    }
+
+    #[test]
+    fn test_usage_recorded_without_response() {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .build();
+        let metrics = LlmMetrics::new(&provider.meter("test"));
+        let usage = CommonUsage { input_tokens: 7, output_tokens: 3, ..Default::default() };
+
+        metrics.record_usage(ClientLlm::OpenAI, "gpt-4o", true, Some(&usage), Duration::from_millis(900));
+        metrics.record_usage(ClientLlm::OpenAI, "gpt-4o", false, None, Duration::from_millis(100));
+
+        provider.force_flush().unwrap();
+        let finished = exporter.get_finished_metrics().unwrap();
+
+        assert_eq!(counter_total(&finished, "llm.requests", Some(("llm.outcome", "success"))), 1);
+        assert_eq!(counter_total(&finished, "llm.tokens", Some(("token.type", "input"))), 7);
+        assert_eq!(counter_total(&finished, "llm.errors", None), 1);
+    }
+}
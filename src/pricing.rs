@@ -0,0 +1,161 @@
+//! Per-model token prices for estimating the cost of a request.
+//!
+//! The registry ships with list prices for common models and can be extended or overridden at
+//! runtime with `set_price`, e.g. for negotiated rates or models released after this crate.
+//! Models are matched by the longest registered prefix, so `"gpt-4o"` also prices dated
+//! versions such as `"gpt-4o-2024-08-06"`.
+//!
+//! ```
+//! use llm_bridge::client::ClientLlm;
+//! use llm_bridge::pricing::{self, ModelPrice};
+//! use llm_bridge::response::CommonUsage;
+//!
+//! pricing::set_price("my-fine-tune", ModelPrice::new(3.0, 12.0));
+//! let usage = CommonUsage { input_tokens: 1_000_000, output_tokens: 500_000, ..Default::default() };
+//! assert_eq!(usage.cost(&ClientLlm::OpenAI, "my-fine-tune"), Some(9.0));
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, PoisonError, RwLock};
+use crate::client::ClientLlm;
+use crate::response::CommonUsage;
+
+/// The price of a model in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    /// The price of input tokens read from the prompt cache; `input` when `None`.
+    pub cache_read: Option<f64>,
+    /// The price of input tokens written to the prompt cache; `input` when `None`.
+    pub cache_write: Option<f64>,
+}
+
+impl ModelPrice {
+    pub const fn new(input: f64, output: f64) -> Self {
+        ModelPrice { input, output, cache_read: None, cache_write: None }
+    }
+
+    /// Sets the prices of prompt cache reads and writes.
+    pub const fn with_cache(mut self, cache_read: f64, cache_write: f64) -> Self {
+        self.cache_read = Some(cache_read);
+        self.cache_write = Some(cache_write);
+        self
+    }
+
+    /// Returns the estimated cost of `usage` reported by `provider` in USD.
+    ///
+    /// Anthropic and Bedrock report cached input tokens separately from `input_tokens`, while
+    /// the other providers include them in it.
+    pub fn cost(&self, usage: &CommonUsage, provider: &ClientLlm) -> f64 {
+        let cache_read = usage.cache_read_input_tokens.unwrap_or(0);
+        let cache_write = usage.cache_creation_input_tokens.unwrap_or(0);
+        let cache_reported_separately = match provider {
+            ClientLlm::Anthropic => true,
+            #[cfg(feature = "bedrock")]
+            ClientLlm::Bedrock => true,
+            _ => false,
+        };
+        let uncached = if cache_reported_separately {
+            usage.input_tokens
+        } else {
+            usage.input_tokens.saturating_sub(cache_read)
+        };
+        let tokens = uncached as f64 * self.input
+            + cache_read as f64 * self.cache_read.unwrap_or(self.input)
+            + cache_write as f64 * self.cache_write.unwrap_or(self.input)
+            + usage.output_tokens as f64 * self.output;
+        tokens / 1_000_000.0
+    }
+}
+
+/// List prices at the time of writing, in USD per million tokens.
+const BUILTIN_PRICES: &[(&str, ModelPrice)] = &[
+    ("claude-3-haiku", ModelPrice::new(0.25, 1.25).with_cache(0.03, 0.30)),
+    ("claude-3-5-haiku", ModelPrice::new(0.80, 4.0).with_cache(0.08, 1.0)),
+    ("claude-3-sonnet", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-5-sonnet", ModelPrice::new(3.0, 15.0).with_cache(0.30, 3.75)),
+    ("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0).with_cache(0.30, 3.75)),
+    ("claude-sonnet-4", ModelPrice::new(3.0, 15.0).with_cache(0.30, 3.75)),
+    ("claude-3-opus", ModelPrice::new(15.0, 75.0).with_cache(1.50, 18.75)),
+    ("claude-opus-4", ModelPrice::new(15.0, 75.0).with_cache(1.50, 18.75)),
+    ("gpt-3.5-turbo", ModelPrice::new(0.50, 1.50)),
+    ("gpt-4-turbo", ModelPrice::new(10.0, 30.0)),
+    ("gpt-4o", ModelPrice::new(2.50, 10.0).with_cache(1.25, 2.50)),
+    ("gpt-4o-mini", ModelPrice::new(0.15, 0.60).with_cache(0.075, 0.15)),
+    ("o1", ModelPrice::new(15.0, 60.0).with_cache(7.50, 15.0)),
+    ("o1-mini", ModelPrice::new(1.10, 4.40).with_cache(0.55, 1.10)),
+    ("o1-pro", ModelPrice::new(150.0, 600.0)),
+    ("o3-mini", ModelPrice::new(1.10, 4.40).with_cache(0.55, 1.10)),
+    ("gemini-1.5-flash", ModelPrice::new(0.075, 0.30)),
+    ("gemini-1.5-pro", ModelPrice::new(1.25, 5.0)),
+    ("gemini-2.0-flash", ModelPrice::new(0.10, 0.40)),
+    ("mistral-large", ModelPrice::new(2.0, 6.0)),
+    ("mistral-small", ModelPrice::new(0.20, 0.60)),
+    ("grok-2", ModelPrice::new(2.0, 10.0)),
+];
+
+fn overrides() -> &'static RwLock<HashMap<String, ModelPrice>> {
+    static OVERRIDES: OnceLock<RwLock<HashMap<String, ModelPrice>>> = OnceLock::new();
+    OVERRIDES.get_or_init(Default::default)
+}
+
+/// Registers the price of `model` and the models it prefixes, replacing any built-in price.
+pub fn set_price(model: &str, price: ModelPrice) {
+    overrides().write().unwrap_or_else(PoisonError::into_inner).insert(model.to_string(), price);
+}
+
+/// Returns the price of `model`, from the longest registered prefix of it. Prices set with
+/// `set_price` take precedence over built-in prices for the same prefix.
+pub fn price(model: &str) -> Option<ModelPrice> {
+    let overrides = overrides().read().unwrap_or_else(PoisonError::into_inner);
+    BUILTIN_PRICES.iter()
+        .map(|(name, price)| (*name, *price))
+        .chain(overrides.iter().map(|(name, price)| (name.as_str(), *price)))
+        .filter(|(name, _)| model.starts_with(name))
+        // `max_by_key` returns the last maximum, so overrides win ties.
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, price)| price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_lookup() {
+        assert_eq!(price("gpt-4o-2024-08-06"), Some(ModelPrice::new(2.50, 10.0).with_cache(1.25, 2.50)));
+        assert_eq!(price("gpt-4o-mini-2024-07-18").unwrap().input, 0.15);
+        assert_eq!(price("o1-pro-2025-03-19"), Some(ModelPrice::new(150.0, 600.0)));
+        assert_eq!(price("unknown-model"), None);
+
+        // The registry is shared by every test, so the override uses a model no other test prices.
+        set_price("gemini-1.5-flash-test-override", ModelPrice::new(0.0375, 0.15));
+        assert_eq!(price("gemini-1.5-flash-test-override-001"), Some(ModelPrice::new(0.0375, 0.15)));
+        assert_eq!(price("gemini-1.5-flash-002"), Some(ModelPrice::new(0.075, 0.30)));
+    }
+
+    #[test]
+    fn test_cost() {
+        let price = ModelPrice::new(3.0, 15.0).with_cache(0.30, 3.75);
+        let anthropic = CommonUsage {
+            input_tokens: 1000,
+            output_tokens: 1000,
+            cache_read_input_tokens: Some(10_000),
+            cache_creation_input_tokens: Some(0),
+            ..Default::default()
+        };
+        assert!((price.cost(&anthropic, &ClientLlm::Anthropic) - 0.021).abs() < 1e-9);
+
+        let openai = CommonUsage {
+            input_tokens: 11_000,
+            output_tokens: 1000,
+            cache_read_input_tokens: Some(10_000),
+            ..Default::default()
+        };
+        assert!((price.cost(&openai, &ClientLlm::OpenAI) - 0.021).abs() < 1e-9);
+
+        let without_cache_writes = CommonUsage { cache_creation_input_tokens: None, ..anthropic };
+        assert!((price.cost(&without_cache_writes, &ClientLlm::Anthropic) - 0.021).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,389 @@
+//! OpenAI Realtime API support over WebSocket.
+//!
+//! Enabled with the `realtime` feature. `RealtimeClient::connect` opens a session, configures
+//! it with a `SessionConfig` (modalities, voice, audio formats, tools), and returns a
+//! `RealtimeSession` for exchanging typed `ClientEvent`s and `ServerEvent`s.
+//!
+//! ```no_run
+//! # use llm_bridge::realtime::{Modality, RealtimeClient, ServerEvent, SessionConfig};
+//! # async fn example() -> Result<(), llm_bridge::error::ApiError> {
+//! let client = RealtimeClient::new("api-key".to_string());
+//! let config = SessionConfig { modalities: vec![Modality::Text], ..Default::default() };
+//! let mut session = client.connect(config).await?;
+//! session.send_text("Hello!").await?;
+//! while let Some(event) = session.next_event().await {
+//!     match event? {
+//!         ServerEvent::ResponseTextDelta { delta, .. } => print!("{}", delta),
+//!         ServerEvent::ResponseDone { .. } => break,
+//!         _ => {}
+//!     }
+//! }
+//! session.close().await
+//! # }
+//! ```
+
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use crate::client::redact;
+use crate::error::ApiError;
+use crate::tool::Tool;
+
+const REALTIME_API_URL: &str = "wss://api.openai.com/v1/realtime";
+const DEFAULT_REALTIME_MODEL: &str = "gpt-4o-realtime-preview";
+
+/// Opens sessions with the OpenAI Realtime API.
+pub struct RealtimeClient {
+    api_key: String,
+    base_url: String,
+    model: String,
+}
+
+impl RealtimeClient {
+    pub fn new(api_key: String) -> Self {
+        RealtimeClient {
+            api_key,
+            base_url: REALTIME_API_URL.to_string(),
+            model: DEFAULT_REALTIME_MODEL.to_string(),
+        }
+    }
+
+    /// Sets the realtime model. Defaults to `gpt-4o-realtime-preview`.
+    pub fn with_model(mut self, model: &str) -> Self {
+        self.model = model.to_string();
+        self
+    }
+
+    /// Overrides the WebSocket endpoint, e.g. for a proxy.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Opens a session and sends `config` as its initial `session.update`.
+    ///
+    /// Handshake failures are returned as `ApiError::ClientError` or `ApiError::ServerError`
+    /// according to the HTTP status.
+    pub async fn connect(&self, config: SessionConfig) -> Result<RealtimeSession, ApiError> {
+        let url = format!("{}?model={}", self.base_url, self.model);
+        let mut request = url.into_client_request().map_err(|e| self.to_api_error(e))?;
+        let headers = request.headers_mut();
+        headers.insert("Authorization", header_value(&format!("Bearer {}", self.api_key))?);
+        headers.insert("OpenAI-Beta", HeaderValue::from_static("realtime=v1"));
+        let (socket, _response) = tokio_tungstenite::connect_async(request).await
+            .map_err(|e| self.to_api_error(e))?;
+
+        let mut session = RealtimeSession { socket, secret: self.api_key.clone() };
+        session.send(ClientEvent::SessionUpdate { session: config }).await?;
+        Ok(session)
+    }
+
+    fn to_api_error(&self, error: tungstenite::Error) -> ApiError {
+        to_api_error(error, &self.api_key)
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, ApiError> {
+    HeaderValue::from_str(value).map_err(|_| ApiError::InvalidUsage("API key is not a valid header value".to_string()))
+}
+
+fn to_api_error(error: tungstenite::Error, secret: &str) -> ApiError {
+    match error {
+        tungstenite::Error::Http(response) => {
+            let body = response.body().as_deref().map(String::from_utf8_lossy).unwrap_or_default();
+            let message = format!("Status: {} - Error: {}", response.status(), redact(&body, &[secret]));
+            if response.status().is_server_error() {
+                ApiError::ServerError(message)
+            } else {
+                ApiError::ClientError(message)
+            }
+        }
+        tungstenite::Error::Io(e) => ApiError::IoError(e),
+        other => ApiError::ServerError(format!("WebSocket error: {}", other)),
+    }
+}
+
+/// An open Realtime session.
+pub struct RealtimeSession {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    secret: String,
+}
+
+impl RealtimeSession {
+    /// Sends an event to the server.
+    pub async fn send(&mut self, event: ClientEvent) -> Result<(), ApiError> {
+        let text = serde_json::to_string(&event)?;
+        self.socket.send(Message::Text(text)).await.map_err(|e| to_api_error(e, &self.secret))
+    }
+
+    /// Returns the next event from the server, or `None` once the session is closed. Events
+    /// this crate doesn't model are returned as `ServerEvent::Unknown`.
+    pub async fn next_event(&mut self) -> Option<Result<ServerEvent, ApiError>> {
+        loop {
+            let message = match self.socket.next().await? {
+                Ok(message) => message,
+                Err(e) => return Some(Err(to_api_error(e, &self.secret))),
+            };
+            match message {
+                Message::Text(text) => return Some(serde_json::from_str(&text).map_err(ApiError::from)),
+                Message::Close(_) => return None,
+                _ => continue,
+            }
+        }
+    }
+
+    /// Adds a user text message to the conversation and asks the model to respond.
+    pub async fn send_text(&mut self, text: &str) -> Result<(), ApiError> {
+        self.send(ClientEvent::ConversationItemCreate {
+            item: json!({
+                "type": "message",
+                "role": "user",
+                "content": [{"type": "input_text", "text": text}]
+            }),
+        }).await?;
+        self.send(ClientEvent::ResponseCreate { response: None }).await
+    }
+
+    /// Appends audio in the session's input format to the input buffer.
+    pub async fn append_audio(&mut self, audio: &[u8]) -> Result<(), ApiError> {
+        let audio = base64::engine::general_purpose::STANDARD.encode(audio);
+        self.send(ClientEvent::InputAudioBufferAppend { audio }).await
+    }
+
+    /// Closes the session.
+    pub async fn close(mut self) -> Result<(), ApiError> {
+        self.socket.close(None).await.map_err(|e| to_api_error(e, &self.secret))
+    }
+}
+
+/// The kinds of output a session produces.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Modality {
+    Text,
+    Audio,
+}
+
+/// Session settings sent with `session.update`. Unset fields keep the server's defaults.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct SessionConfig {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub modalities: Vec<Modality>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// The voice for audio output, e.g. `alloy`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+    /// `pcm16`, `g711_ulaw` or `g711_alaw`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_audio_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_audio_format: Option<String>,
+    /// Voice activity detection settings, or `Value::Null` to turn it off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn_detection: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty", serialize_with = "serialize_tools")]
+    pub tools: Vec<Tool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+}
+
+fn serialize_tools<S: serde::Serializer>(tools: &[Tool], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(tools.iter().map(Tool::to_openai_realtime_format))
+}
+
+/// Events sent by the client.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum ClientEvent {
+    #[serde(rename = "session.update")]
+    SessionUpdate { session: SessionConfig },
+    /// Base64-encoded audio; see `RealtimeSession::append_audio`.
+    #[serde(rename = "input_audio_buffer.append")]
+    InputAudioBufferAppend { audio: String },
+    #[serde(rename = "input_audio_buffer.commit")]
+    InputAudioBufferCommit,
+    #[serde(rename = "input_audio_buffer.clear")]
+    InputAudioBufferClear,
+    /// Adds a message, function call or function call output to the conversation.
+    #[serde(rename = "conversation.item.create")]
+    ConversationItemCreate { item: Value },
+    /// Asks the model to respond, optionally overriding session settings for this response.
+    #[serde(rename = "response.create")]
+    ResponseCreate {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        response: Option<Value>,
+    },
+    #[serde(rename = "response.cancel")]
+    ResponseCancel,
+}
+
+/// Events sent by the server. Event types this crate doesn't model deserialize to `Unknown`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ServerEvent {
+    #[serde(rename = "error")]
+    Error { error: RealtimeError },
+    #[serde(rename = "session.created")]
+    SessionCreated { session: Value },
+    #[serde(rename = "session.updated")]
+    SessionUpdated { session: Value },
+    #[serde(rename = "input_audio_buffer.speech_started")]
+    SpeechStarted {
+        #[serde(default)]
+        item_id: String,
+    },
+    #[serde(rename = "input_audio_buffer.speech_stopped")]
+    SpeechStopped {
+        #[serde(default)]
+        item_id: String,
+    },
+    #[serde(rename = "response.created")]
+    ResponseCreated { response: Value },
+    #[serde(rename = "response.text.delta")]
+    ResponseTextDelta {
+        #[serde(default)]
+        response_id: String,
+        #[serde(default)]
+        item_id: String,
+        delta: String,
+    },
+    #[serde(rename = "response.text.done")]
+    ResponseTextDone {
+        #[serde(default)]
+        response_id: String,
+        #[serde(default)]
+        item_id: String,
+        text: String,
+    },
+    /// A fragment of base64-encoded output audio.
+    #[serde(rename = "response.audio.delta")]
+    ResponseAudioDelta {
+        #[serde(default)]
+        response_id: String,
+        #[serde(default)]
+        item_id: String,
+        delta: String,
+    },
+    #[serde(rename = "response.audio_transcript.delta")]
+    ResponseAudioTranscriptDelta {
+        #[serde(default)]
+        response_id: String,
+        #[serde(default)]
+        item_id: String,
+        delta: String,
+    },
+    #[serde(rename = "response.function_call_arguments.done")]
+    FunctionCallArgumentsDone {
+        #[serde(default)]
+        response_id: String,
+        call_id: String,
+        name: String,
+        arguments: String,
+    },
+    /// The response finished; `response` includes its status and token usage.
+    #[serde(rename = "response.done")]
+    ResponseDone { response: Value },
+    #[serde(other)]
+    Unknown,
+}
+
+/// An error reported by the server. Most errors leave the session open.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RealtimeError {
+    #[serde(rename = "type")]
+    pub error_type: String,
+    #[serde(default)]
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl ServerEvent {
+    /// Returns the audio in a `ResponseAudioDelta` event, decoded from base64.
+    pub fn audio(&self) -> Option<Result<Vec<u8>, ApiError>> {
+        let ServerEvent::ResponseAudioDelta { delta, .. } = self else {
+            return None;
+        };
+        Some(base64::engine::general_purpose::STANDARD.decode(delta)
+            .map_err(|e| ApiError::ServerError(format!("Invalid audio data: {}", e))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)] // The handshake callback's error type is set by tungstenite.
+    async fn test_realtime_session() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut headers = None;
+            let mut socket = tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response: Response| {
+                headers = Some((request.uri().to_string(), request.headers().clone()));
+                Ok(response)
+            }).await.unwrap();
+            let mut received = Vec::new();
+            for _ in 0..3 {
+                let Some(Ok(Message::Text(text))) = socket.next().await else { panic!("expected a text message") };
+                received.push(serde_json::from_str::<Value>(&text).unwrap());
+            }
+            for event in [
+                json!({"type": "response.text.delta", "event_id": "e1", "response_id": "r1", "item_id": "i1", "output_index": 0, "content_index": 0, "delta": "Hi"}),
+                json!({"type": "rate_limits.updated", "rate_limits": []}),
+                json!({"type": "response.done", "response": {"id": "r1", "status": "completed"}}),
+            ] {
+                socket.send(Message::Text(event.to_string())).await.unwrap();
+            }
+            socket.close(None).await.unwrap();
+            (headers.unwrap(), received)
+        });
+
+        let client = RealtimeClient::new("key".to_string())
+            .with_base_url(&format!("ws://{}/v1/realtime", address));
+        let tool = Tool::builder().name("get_time").description("Gets the time").build().unwrap();
+        let config = SessionConfig { modalities: vec![Modality::Text], tools: vec![tool], ..Default::default() };
+        let mut session = client.connect(config).await.unwrap();
+        session.send_text("Hello!").await.unwrap();
+        let mut events = Vec::new();
+        while let Some(event) = session.next_event().await {
+            events.push(event.unwrap());
+        }
+
+        assert_eq!(events, vec![
+            ServerEvent::ResponseTextDelta { response_id: "r1".to_string(), item_id: "i1".to_string(), delta: "Hi".to_string() },
+            ServerEvent::Unknown,
+            ServerEvent::ResponseDone { response: json!({"id": "r1", "status": "completed"}) },
+        ]);
+        let ((uri, headers), received) = server.await.unwrap();
+        assert_eq!(uri, "/v1/realtime?model=gpt-4o-realtime-preview");
+        assert_eq!(headers["authorization"], "Bearer key");
+        assert_eq!(headers["openai-beta"], "realtime=v1");
+        assert_eq!(received[0]["type"], "session.update");
+        assert_eq!(received[0]["session"]["modalities"], json!(["text"]));
+        assert_eq!(received[0]["session"]["tools"][0]["type"], "function");
+        assert_eq!(received[0]["session"]["tools"][0]["name"], "get_time");
+        assert_eq!(received[1]["item"]["content"][0], json!({"type": "input_text", "text": "Hello!"}));
+        assert_eq!(received[2], json!({"type": "response.create"}));
+    }
+
+    #[test]
+    fn test_audio_delta_decoding() {
+        let event: ServerEvent = serde_json::from_value(json!({
+            "type": "response.audio.delta", "response_id": "r1", "item_id": "i1", "delta": "AAEC"
+        })).unwrap();
+
+        assert_eq!(event.audio().unwrap().unwrap(), vec![0, 1, 2]);
+        assert!(ServerEvent::Unknown.audio().is_none());
+    }
+}
@@ -0,0 +1,60 @@
+//! An organization-level registry of approved models.
+//!
+//! Attaching a `ModelRegistry` to an `LlmClient` restricts every request made through that client
+//! to an allowlist of models, and lets applications refer to models by friendly names
+//! (e.g. `"fast"`) that map to concrete model versions.
+
+use std::collections::{HashMap, HashSet};
+use crate::error::ApiError;
+
+#[derive(Debug, Clone, Default)]
+pub struct ModelRegistry {
+    allowed: HashSet<String>,
+    aliases: HashMap<String, String>,
+}
+
+impl ModelRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a concrete model to the allowlist.
+    pub fn allow(mut self, model: &str) -> Self {
+        self.allowed.insert(model.to_string());
+        self
+    }
+
+    /// Maps a friendly name to a concrete model, which is also added to the allowlist.
+    pub fn alias(mut self, name: &str, model: &str) -> Self {
+        self.aliases.insert(name.to_string(), model.to_string());
+        self.allow(model)
+    }
+
+    /// Resolves a friendly name or concrete model to the concrete model to request, returning
+    /// `ApiError::InvalidUsage` when the model is not approved.
+    pub fn resolve(&self, model: &str) -> Result<String, ApiError> {
+        let resolved = self.aliases.get(model).map(String::as_str).unwrap_or(model);
+        if self.allowed.contains(resolved) {
+            Ok(resolved.to_string())
+        } else {
+            Err(ApiError::InvalidUsage(format!("Model '{}' is not in the model registry", model)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let registry = ModelRegistry::new()
+            .allow("gpt-4o")
+            .alias("fast", "claude-3-haiku-20240307");
+
+        assert_eq!(registry.resolve("gpt-4o").unwrap(), "gpt-4o");
+        assert_eq!(registry.resolve("fast").unwrap(), "claude-3-haiku-20240307");
+        assert_eq!(registry.resolve("claude-3-haiku-20240307").unwrap(), "claude-3-haiku-20240307");
+        assert!(matches!(registry.resolve("gpt-3.5-turbo"), Err(ApiError::InvalidUsage(_))));
+    }
+}
@@ -1,27 +1,97 @@
-//! This module defines the data models used for interacting with different LLM APIs.
+//! This module defines the canonical request types sent to LLM APIs.
 //!
-//! The main strategy employed to support multiple LLM APIs is to define separate response
-//! structs for each API (`AnthropicResponse` and `OpenAIResponse`) and use an enum
-//! (`ResponseMessage`) to represent the different response types. The `ResponseMessage` enum
-//! provides a unified interface for accessing common fields and methods across different APIs.
+//! `Message` is a single conversation turn and `RequestBody` the Anthropic request payload.
+//! Responses live in the `response` module; the public layout is:
 //!
-//! To add support for a new LLM API:
-//! 1. Define a new response struct for the API, implementing the necessary deserialization logic.
-//! 2. Add a new variant to the `ResponseMessage` enum for the new API response type.
-//! 3. Update the implementation of the `ResponseMessage` methods to handle the new variant and
-//!    provide the appropriate logic for accessing the fields and data.
+//! ```
+//! use llm_bridge::request::{Message, RequestBody, RequestOptions};
+//! use llm_bridge::response::{AnthropicContentBlock, AnthropicResponse, OpenAIResponse, ResponseMessage, ToolResponse};
 //!
-//! By following this approach, the `ResponseMessage` enum acts as a common interface for handling
-//! responses from different LLM APIs, while the individual response structs encapsulate the
-//! specific details of each API's response format.
+//! use llm_bridge::request::Role;
+//!
+//! let message = Message { role: Role::User, content: "Hello!".into(), ..Default::default() };
+//! let body = RequestBody { messages: vec![message], ..Default::default() };
+//! assert_eq!(body.messages[0].content, "Hello!");
+//! ```
 
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
+use crate::response::OpenAIToolCall;
+
+/// The author of a conversation turn, normalized across providers.
+///
+/// Serializes to the OpenAI and Anthropic role names; OpenAI's `developer` and Gemini's `model`
+/// are also accepted when deserializing.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Instructions for the model (OpenAI `system` or `developer`).
+    #[serde(alias = "developer")]
+    System,
+    #[default]
+    User,
+    /// The model (Gemini `model`).
+    #[serde(alias = "model")]
+    Assistant,
+    /// The output of a tool call (OpenAI `tool`).
+    Tool,
+}
+
+impl Role {
+    /// Returns the role's name in the OpenAI and Anthropic wire format.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::System => "system",
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::Tool => "tool",
+        }
+    }
+
+    /// Parses a provider role name, mapping Gemini's `model` and OpenAI's `developer` to their
+    /// common equivalents. Returns `None` for unknown roles.
+    pub fn from_provider(role: &str) -> Option<Role> {
+        match role {
+            "system" | "developer" => Some(Role::System),
+            "user" => Some(Role::User),
+            "assistant" | "model" => Some(Role::Assistant),
+            "tool" => Some(Role::Tool),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
 /// Represents a message in the conversation.
+///
+/// `content` is usually a plain string, but may also be an array of content blocks
+/// (e.g. Anthropic `tool_use`/`tool_result` blocks) or `null` (an OpenAI assistant turn
+/// that only contains tool calls).
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Message {
-    pub role: String,
-    pub content: String,
+    pub role: Role,
+    pub content: serde_json::Value,
+    /// The tool calls made by an OpenAI assistant turn.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<OpenAIToolCall>>,
+    /// The id of the OpenAI tool call a `tool` message is responding to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl Message {
+    /// Creates a message with the given role and content, e.g. for building a history to pass
+    /// to `RequestBuilder::messages`.
+    pub fn new(role: Role, content: impl Into<serde_json::Value>) -> Self {
+        Message { role, content: content.into(), ..Default::default() }
+    }
 }
 
 /// Represents the request body sent to the Anthropic API.
@@ -34,6 +104,80 @@ pub struct RequestBody {
     pub system: String,
 }
 
+/// The role the system prompt is sent with in OpenAI-style message lists. See
+/// `RequestBuilder::system_role`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SystemRole {
+    /// The classic `system` role, accepted by every OpenAI-compatible API.
+    System,
+    /// The `developer` role that replaces `system` for OpenAI reasoning models.
+    Developer,
+}
+
+/// How much effort a reasoning model spends thinking before it answers. See
+/// `RequestBuilder::reasoning_effort`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    /// The thinking token budget used for providers that take a budget rather than an effort
+    /// level (Anthropic extended thinking and Gemini `thinkingBudget`).
+    pub fn budget_tokens(self) -> u32 {
+        match self {
+            ReasoningEffort::Low => 1024,
+            ReasoningEffort::Medium => 4096,
+            ReasoningEffort::High => 16384,
+        }
+    }
+}
 
+/// The HTTP request `RequestBuilder::send` would make, as returned by
+/// `RequestBuilder::dry_run`. Headers that carry credentials are redacted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRun {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// The serialized request body, exactly as it would be sent.
+    pub body: String,
+}
 
+impl DryRun {
+    /// Formats the request as an equivalent `curl` command.
+    pub fn to_curl(&self) -> String {
+        let mut command = format!("curl -X {} {}", self.method, shell_quote(&self.url));
+        for (name, value) in &self.headers {
+            command.push_str(&format!(" \\\n  -H {}", shell_quote(&format!("{}: {}", name, value))));
+        }
+        command.push_str(&format!(" \\\n  -d {}", shell_quote(&self.body)));
+        command
+    }
+}
 
+/// Quotes `text` as a single POSIX shell word.
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Per-request transport options that are applied when the request is sent, rather than
+/// rendered into the request body.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Overrides the API key the client was constructed with for this request only.
+    pub api_key: Option<String>,
+    /// Additional HTTP headers sent with the request. Headers required by the provider
+    /// (authentication, API version, content type) always take precedence over these.
+    pub headers: HashMap<String, String>,
+    /// How long a streaming response may go without receiving data before it fails with
+    /// `ApiError::StreamIdleTimeout`.
+    pub stream_idle_timeout: Option<Duration>,
+    /// How long the request, including retries, may take before it fails with
+    /// `ApiError::Timeout`.
+    pub timeout: Option<Duration>,
+}
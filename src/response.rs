@@ -1,7 +1,97 @@
+//! This module defines the data models used for interacting with different LLM APIs.
+//!
+//! The main strategy employed to support multiple LLM APIs is to define separate response
+//! structs for each API (`AnthropicResponse` and `OpenAIResponse`) and use an enum
+//! (`ResponseMessage`) to represent the different response types. The `ResponseMessage` enum
+//! provides a unified interface for accessing common fields and methods across different APIs.
+//!
+//! To add support for a new LLM API:
+//! 1. Define a new response struct for the API, implementing the necessary deserialization logic.
+//! 2. Add a new variant to the `ResponseMessage` enum for the new API response type.
+//! 3. Update the implementation of the `ResponseMessage` methods to handle the new variant and
+//!    provide the appropriate logic for accessing the fields and data.
+//!
+//! By following this approach, the `ResponseMessage` enum acts as a common interface for handling
+//! responses from different LLM APIs, while the individual response structs encapsulate the
+//! specific details of each API's response format.
+
 use std::fmt;
-use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use crate::client::ClientLlm;
+use crate::error::ApiError;
+use crate::pricing;
+use crate::request::{Message, Role};
+
+
+
+/// How a response was served, as returned by `ResponseMessage::meta`.
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    /// The time from sending the first attempt until the final response's headers arrived,
+    /// including any retry delays.
+    pub latency: Duration,
+    /// The number of times the request was retried before this response.
+    pub retries: u32,
+    pub http_status: u16,
+    /// The provider's id for the request (`request-id`, `x-request-id` or
+    /// `x-amzn-requestid`), useful when reporting issues to the provider.
+    pub request_id: Option<String>,
+    pub provider: ClientLlm,
+    /// The model that served the request, as reported in the response.
+    pub model: String,
+    /// The provider's rate-limit headers, when it sent any.
+    pub rate_limit: Option<RateLimitInfo>,
+}
+
+/// The state of one rate limit, as reported by the provider's response headers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimit {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// When the limit resets, as sent by the provider: an RFC 3339 timestamp for Anthropic and a
+    /// duration such as `6m0s` for OpenAI.
+    pub reset: Option<String>,
+}
 
+/// The rate limits reported by `anthropic-ratelimit-*` or OpenAI-style `x-ratelimit-*` response
+/// headers, so applications can throttle before they are rejected with a 429.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimitInfo {
+    pub requests: RateLimit,
+    pub tokens: RateLimit,
+    /// Anthropic's separate input token limit.
+    pub input_tokens: RateLimit,
+    /// Anthropic's separate output token limit.
+    pub output_tokens: RateLimit,
+}
+
+impl RateLimitInfo {
+    /// Parses the rate-limit headers in `headers`, returning `None` if there are none.
+    pub fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let header = |name: String| headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+        let anthropic = |kind: &str| RateLimit {
+            limit: header(format!("anthropic-ratelimit-{}-limit", kind)).and_then(|value| value.parse().ok()),
+            remaining: header(format!("anthropic-ratelimit-{}-remaining", kind)).and_then(|value| value.parse().ok()),
+            reset: header(format!("anthropic-ratelimit-{}-reset", kind)),
+        };
+        let openai = |kind: &str| RateLimit {
+            limit: header(format!("x-ratelimit-limit-{}", kind)).and_then(|value| value.parse().ok()),
+            remaining: header(format!("x-ratelimit-remaining-{}", kind)).and_then(|value| value.parse().ok()),
+            reset: header(format!("x-ratelimit-reset-{}", kind)),
+        };
+        let or = |first: RateLimit, second: RateLimit| if first == RateLimit::default() { second } else { first };
 
+        let info = RateLimitInfo {
+            requests: or(anthropic("requests"), openai("requests")),
+            tokens: or(anthropic("tokens"), openai("tokens")),
+            input_tokens: anthropic("input-tokens"),
+            output_tokens: anthropic("output-tokens"),
+        };
+        (info != RateLimitInfo::default()).then_some(info)
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct OpenAIResponse {
@@ -10,14 +100,77 @@ pub struct OpenAIResponse {
     pub created: i64,
     pub model: String,
     pub choices: Vec<OpenAIChoice>,
+    /// Some OpenAI-compatible servers omit usage; it defaults to zero tokens.
+    #[serde(default)]
     pub usage: OpenAIUsage,
+    #[serde(default)]
+    pub system_fingerprint: Option<String>,
+    /// The URLs of the sources a Perplexity answer cites, in the order of its `[1]`, `[2]`, ...
+    /// markers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citations: Option<Vec<String>>,
+    /// The web search results a Perplexity answer is grounded on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_results: Option<Vec<SearchResult>>,
+    /// Fields this crate doesn't model, kept so new provider fields are still available.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+    /// The response body exactly as received from the API. See `ResponseMessage::raw_body`.
+    #[serde(skip)]
+    pub raw_body: Option<String>,
+    /// How the response was served. See `ResponseMessage::meta`.
+    #[serde(skip)]
+    pub meta: Option<ResponseMeta>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
-pub(crate) struct OpenAIUsage {
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct OpenAIUsage {
     pub prompt_tokens: usize,
     pub completion_tokens: usize,
     pub total_tokens: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens_details: Option<OpenAIPromptTokensDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_tokens_details: Option<OpenAICompletionTokensDetails>,
+}
+
+/// The breakdown of `OpenAIUsage::prompt_tokens`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct OpenAIPromptTokensDetails {
+    /// Prompt tokens read from the prompt cache.
+    #[serde(default)]
+    pub cached_tokens: Option<usize>,
+    #[serde(default)]
+    pub audio_tokens: Option<usize>,
+}
+
+/// The breakdown of `OpenAIUsage::completion_tokens`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct OpenAICompletionTokensDetails {
+    /// Tokens a reasoning model spent thinking, which are billed but not part of the answer.
+    #[serde(default)]
+    pub reasoning_tokens: Option<usize>,
+    #[serde(default)]
+    pub audio_tokens: Option<usize>,
+}
+
+impl From<&OpenAIUsage> for CommonUsage {
+    fn from(usage: &OpenAIUsage) -> Self {
+        let prompt = usage.prompt_tokens_details.clone().unwrap_or_default();
+        let completion = usage.completion_tokens_details.clone().unwrap_or_default();
+        let audio_tokens = match (prompt.audio_tokens, completion.audio_tokens) {
+            (None, None) => None,
+            (prompt, completion) => Some(prompt.unwrap_or(0) + completion.unwrap_or(0)),
+        };
+        CommonUsage {
+            input_tokens: usage.prompt_tokens,
+            output_tokens: usage.completion_tokens,
+            cache_read_input_tokens: prompt.cached_tokens,
+            cache_creation_input_tokens: None,
+            reasoning_tokens: completion.reasoning_tokens,
+            audio_tokens,
+        }
+    }
 }
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AnthropicResponse {
@@ -28,10 +181,32 @@ pub struct AnthropicResponse {
     pub stop_reason: String,
     pub stop_sequence: Option<String>,
     pub usage: AnthropicUsage,
+    /// Fields this crate doesn't model, kept so new provider fields are still available.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+    /// The response body exactly as received from the API. See `ResponseMessage::raw_body`.
+    #[serde(skip)]
+    pub raw_body: Option<String>,
+    /// How the response was served. See `ResponseMessage::meta`.
+    #[serde(skip)]
+    pub meta: Option<ResponseMeta>,
+}
+
+impl AnthropicResponse {
+    /// Returns the content blocks other than extended thinking and unknown block types.
+    pub(crate) fn answer_content(&self) -> impl Iterator<Item = &AnthropicContentBlock> {
+        self.content.iter().filter(|block| !matches!(block,
+            AnthropicContentBlock::Thinking { .. }
+            | AnthropicContentBlock::RedactedThinking { .. }
+            | AnthropicContentBlock::Unknown(_)))
+    }
 }
 
 /// Represents a block of content in the API response.
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// Blocks are deserialized by their `type`, so block types this crate doesn't model, such as
+/// `server_tool_use`, become `Unknown` even when their fields resemble a modelled block.
+#[derive(Serialize, Debug)]
 #[serde(untagged)]
 pub enum AnthropicContentBlock {
     /// Represents a text content block in the Anthropic API response.
@@ -56,6 +231,287 @@ pub enum AnthropicContentBlock {
         /// This allows for flexibility in the structure of tool inputs.
         input: serde_json::Value,
     },
+    /// The model's extended thinking, returned before the answer when requested with
+    /// `RequestBuilder::reasoning_effort`. It must be sent back unchanged when continuing a
+    /// tool use conversation, which `to_assistant_message` does.
+    Thinking {
+        /// The type of the content block, always "thinking" for this variant.
+        #[serde(rename = "type")]
+        block_type: String,
+        thinking: String,
+        /// Verifies the thinking was generated by the model.
+        signature: String,
+    },
+    /// Thinking that was flagged by safety systems and is returned encrypted.
+    RedactedThinking {
+        /// The type of the content block, always "redacted_thinking" for this variant.
+        #[serde(rename = "type")]
+        block_type: String,
+        data: String,
+    },
+    /// A block of a type this crate doesn't model yet, kept as received so new block types
+    /// don't fail the whole response.
+    Unknown(serde_json::Value),
+}
+
+/// The block types `AnthropicContentBlock` models, tagged by `type`.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum KnownAnthropicContentBlock {
+    Text { text: String },
+    ToolUse { id: String, name: String, input: serde_json::Value },
+    Thinking { thinking: String, signature: String },
+    RedactedThinking { data: String },
+}
+
+impl<'de> Deserialize<'de> for AnthropicContentBlock {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let known = matches!(value.get("type").and_then(serde_json::Value::as_str),
+            Some("text" | "tool_use" | "thinking" | "redacted_thinking"));
+        if !known {
+            return Ok(AnthropicContentBlock::Unknown(value));
+        }
+        let block = KnownAnthropicContentBlock::deserialize(value).map_err(D::Error::custom)?;
+        Ok(match block {
+            KnownAnthropicContentBlock::Text { text } =>
+                AnthropicContentBlock::Text { text, block_type: "text".to_string() },
+            KnownAnthropicContentBlock::ToolUse { id, name, input } =>
+                AnthropicContentBlock::ToolUse { block_type: "tool_use".to_string(), id, name, input },
+            KnownAnthropicContentBlock::Thinking { thinking, signature } =>
+                AnthropicContentBlock::Thinking { block_type: "thinking".to_string(), thinking, signature },
+            KnownAnthropicContentBlock::RedactedThinking { data } =>
+                AnthropicContentBlock::RedactedThinking { block_type: "redacted_thinking".to_string(), data },
+        })
+    }
+}
+
+/// Represents the response of the Gemini `generateContent` API.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiResponse {
+    /// Empty when the prompt itself was blocked; see `prompt_feedback`.
+    #[serde(default)]
+    pub candidates: Vec<GeminiCandidate>,
+    pub usage_metadata: GeminiUsage,
+    #[serde(default)]
+    pub model_version: Option<String>,
+    #[serde(default)]
+    pub response_id: Option<String>,
+    #[serde(default)]
+    pub prompt_feedback: Option<GeminiPromptFeedback>,
+    /// Fields this crate doesn't model, kept so new provider fields are still available.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+    /// The response body exactly as received from the API. See `ResponseMessage::raw_body`.
+    #[serde(skip)]
+    pub raw_body: Option<String>,
+    /// How the response was served. See `ResponseMessage::meta`.
+    #[serde(skip)]
+    pub meta: Option<ResponseMeta>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiCandidate {
+    /// Missing when the candidate was blocked by a safety filter.
+    #[serde(default)]
+    pub content: GeminiContent,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    #[serde(default)]
+    pub index: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GeminiContent {
+    #[serde(default)]
+    pub role: String,
+    #[serde(default)]
+    pub parts: Vec<GeminiPart>,
+}
+
+/// A single part of Gemini content, holding either text or a function call.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiPart {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_call: Option<GeminiFunctionCall>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GeminiFunctionCall {
+    /// Only returned by some models; the function name is used as the tool call id otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+}
+
+impl GeminiFunctionCall {
+    fn to_tool_response(&self) -> ToolResponse {
+        ToolResponse {
+            id: self.id.clone().unwrap_or_else(|| self.name.clone()),
+            name: self.name.clone(),
+            input: canonicalize_json(self.args.clone()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiUsage {
+    #[serde(default)]
+    pub prompt_token_count: usize,
+    #[serde(default)]
+    pub candidates_token_count: usize,
+    #[serde(default)]
+    pub total_token_count: usize,
+    /// Prompt tokens served from a context cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_content_token_count: Option<usize>,
+    /// Tokens a thinking model spent reasoning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thoughts_token_count: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiPromptFeedback {
+    #[serde(default)]
+    pub block_reason: Option<String>,
+}
+
+/// Represents the response of the AWS Bedrock `Converse` API.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockResponse {
+    pub output: BedrockOutput,
+    pub stop_reason: String,
+    pub usage: BedrockUsage,
+    /// Fields this crate doesn't model, kept so new provider fields are still available.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+    /// The response body exactly as received from the API. See `ResponseMessage::raw_body`.
+    #[serde(skip)]
+    pub raw_body: Option<String>,
+    /// How the response was served. See `ResponseMessage::meta`.
+    #[serde(skip)]
+    pub meta: Option<ResponseMeta>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BedrockOutput {
+    pub message: BedrockMessage,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BedrockMessage {
+    pub role: String,
+    pub content: Vec<BedrockContentBlock>,
+}
+
+/// A single Converse content block, holding either text or a tool use.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockContentBlock {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use: Option<BedrockToolUse>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockToolUse {
+    pub tool_use_id: String,
+    pub name: String,
+    pub input: serde_json::Value,
+}
+
+impl BedrockToolUse {
+    fn to_tool_response(&self) -> ToolResponse {
+        ToolResponse {
+            id: self.tool_use_id.clone(),
+            name: self.name.clone(),
+            input: canonicalize_json(self.input.clone()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BedrockUsage {
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    #[serde(default)]
+    pub total_tokens: usize,
+    /// Input tokens read from the prompt cache, not included in `input_tokens`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<usize>,
+    /// Input tokens written to the prompt cache, not included in `input_tokens`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_input_tokens: Option<usize>,
+}
+
+/// Represents the response of the Ollama native `/api/chat` API.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OllamaResponse {
+    pub model: String,
+    pub created_at: String,
+    pub message: OllamaMessage,
+    pub done: bool,
+    #[serde(default)]
+    pub done_reason: Option<String>,
+    /// Omitted by Ollama when the prompt was served from its cache.
+    #[serde(default)]
+    pub prompt_eval_count: usize,
+    #[serde(default)]
+    pub eval_count: usize,
+    /// Fields this crate doesn't model, kept so new provider fields are still available.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+    /// The response body exactly as received from the API. See `ResponseMessage::raw_body`.
+    #[serde(skip)]
+    pub raw_body: Option<String>,
+    /// How the response was served. See `ResponseMessage::meta`.
+    #[serde(skip)]
+    pub meta: Option<ResponseMeta>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OllamaMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<OllamaToolCall>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OllamaToolCall {
+    pub function: OllamaFunctionCall,
+}
+
+/// A function call made by an Ollama model. Unlike OpenAI, the arguments are a JSON object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OllamaFunctionCall {
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+impl OllamaToolCall {
+    /// Ollama tool calls carry no id, so the function name is used in its place.
+    fn to_tool_response(&self) -> ToolResponse {
+        ToolResponse {
+            id: self.function.name.clone(),
+            name: self.function.name.clone(),
+            input: canonicalize_json(self.function.arguments.clone()),
+        }
+    }
 }
 
 /// Represents the response message received from an LLM API.
@@ -67,9 +523,130 @@ pub enum AnthropicContentBlock {
 pub enum ResponseMessage {
     Anthropic(AnthropicResponse),
     OpenAI(OpenAIResponse),
+    Gemini(GeminiResponse),
+    Bedrock(BedrockResponse),
+    Ollama(OllamaResponse),
 }
 
 impl ResponseMessage {
+    /// Parses a stored response of any provider, choosing the variant from discriminating
+    /// fields rather than untagged trial deserialization: OpenAI responses have
+    /// `"object": "chat.completion"` (or a `choices` array), Anthropic responses have `content`
+    /// and `stop_reason`, Gemini responses have `candidates` or `usageMetadata`, Bedrock
+    /// Converse responses have `output` and `stopReason`, and Ollama responses have `message`
+    /// and `done`.
+    pub fn from_json(value: serde_json::Value) -> Result<Self, ApiError> {
+        let is_openai = value.get("object").and_then(|object| object.as_str()) == Some("chat.completion")
+            || value.get("choices").is_some_and(|choices| choices.is_array());
+        let is_anthropic = value.get("type").and_then(|kind| kind.as_str()) == Some("message")
+            || (value.get("content").is_some() && value.get("stop_reason").is_some());
+        let is_gemini = value.get("candidates").is_some() || value.get("usageMetadata").is_some();
+        let is_bedrock = value.get("output").is_some() && value.get("stopReason").is_some();
+        let is_ollama = value.get("message").is_some() && value.get("done").is_some();
+
+        if is_openai {
+            Ok(ResponseMessage::OpenAI(serde_json::from_value(value)?))
+        } else if is_anthropic {
+            Ok(ResponseMessage::Anthropic(serde_json::from_value(value)?))
+        } else if is_gemini {
+            Ok(ResponseMessage::Gemini(serde_json::from_value(value)?))
+        } else if is_bedrock {
+            Ok(ResponseMessage::Bedrock(serde_json::from_value(value)?))
+        } else if is_ollama {
+            Ok(ResponseMessage::Ollama(serde_json::from_value(value)?))
+        } else {
+            Err(ApiError::ResponseParseError(serde::de::Error::custom(
+                "unrecognized response format, expected an Anthropic, OpenAI, Gemini, Bedrock or Ollama response")))
+        }
+    }
+
+    /// Returns the response body exactly as received from the API, for provider-specific fields
+    /// the unified accessors don't model. `None` for responses collected from a stream, parsed
+    /// with `from_json` or constructed in code.
+    pub fn raw_body(&self) -> Option<&str> {
+        match self {
+            ResponseMessage::Anthropic(response) => response.raw_body.as_deref(),
+            ResponseMessage::OpenAI(response) => response.raw_body.as_deref(),
+            ResponseMessage::Gemini(response) => response.raw_body.as_deref(),
+            ResponseMessage::Bedrock(response) => response.raw_body.as_deref(),
+            ResponseMessage::Ollama(response) => response.raw_body.as_deref(),
+        }
+    }
+
+    /// Returns `raw_body` parsed as JSON.
+    ///
+    /// ```
+    /// # use llm_bridge::response::ResponseMessage;
+    /// # fn example(response: &ResponseMessage) {
+    /// if let Some(raw) = response.raw() {
+    ///     println!("service tier: {}", raw["service_tier"]);
+    /// }
+    /// # }
+    /// ```
+    pub fn raw(&self) -> Option<serde_json::Value> {
+        self.raw_body().and_then(|body| serde_json::from_str(body).ok())
+    }
+
+    /// Returns how the response was served: latency, retries, HTTP status, the provider's
+    /// request id and its rate limits. `None` for responses collected from a stream, parsed with `from_json` or
+    /// constructed in code.
+    ///
+    /// ```
+    /// # use llm_bridge::response::ResponseMessage;
+    /// # fn example(response: &ResponseMessage) {
+    /// if let Some(meta) = response.meta() {
+    ///     println!("{} took {:?} after {} retries", meta.model, meta.latency, meta.retries);
+    /// }
+    /// # }
+    /// ```
+    pub fn meta(&self) -> Option<&ResponseMeta> {
+        match self {
+            ResponseMessage::Anthropic(response) => response.meta.as_ref(),
+            ResponseMessage::OpenAI(response) => response.meta.as_ref(),
+            ResponseMessage::Gemini(response) => response.meta.as_ref(),
+            ResponseMessage::Bedrock(response) => response.meta.as_ref(),
+            ResponseMessage::Ollama(response) => response.meta.as_ref(),
+        }
+    }
+
+    /// Sets the metadata `meta` returns, taking its model from the response.
+    pub(crate) fn with_meta(mut self, mut meta: ResponseMeta) -> Self {
+        meta.model = self.model().to_string();
+        *self.meta_mut() = Some(meta);
+        self
+    }
+
+    /// Sets the provider recorded in `meta`, for clients that delegate to another client.
+    pub(crate) fn with_provider(mut self, provider: ClientLlm) -> Self {
+        if let Some(meta) = self.meta_mut() {
+            meta.provider = provider;
+        }
+        self
+    }
+
+    fn meta_mut(&mut self) -> &mut Option<ResponseMeta> {
+        match self {
+            ResponseMessage::Anthropic(response) => &mut response.meta,
+            ResponseMessage::OpenAI(response) => &mut response.meta,
+            ResponseMessage::Gemini(response) => &mut response.meta,
+            ResponseMessage::Bedrock(response) => &mut response.meta,
+            ResponseMessage::Ollama(response) => &mut response.meta,
+        }
+    }
+
+    /// Sets the body `raw_body` returns.
+    pub(crate) fn with_raw_body(mut self, body: String) -> Self {
+        let raw_body = match &mut self {
+            ResponseMessage::Anthropic(response) => &mut response.raw_body,
+            ResponseMessage::OpenAI(response) => &mut response.raw_body,
+            ResponseMessage::Gemini(response) => &mut response.raw_body,
+            ResponseMessage::Bedrock(response) => &mut response.raw_body,
+            ResponseMessage::Ollama(response) => &mut response.raw_body,
+        };
+        *raw_body = Some(body);
+        self
+    }
+
     /// Returns the text content of the first message in the response.
     ///
     /// # Examples
@@ -83,7 +660,10 @@ impl ResponseMessage {
     ///     model: "".to_string(),
     ///     stop_reason: "".to_string(),
     ///     stop_sequence: None,
-    ///     usage: Default::default(),}
+    ///     usage: Default::default(),
+    ///     extra: Default::default(),
+    ///     raw_body: None,
+    ///     meta: None,}
     /// );
     /// let first_message = response.first_message();
     /// println!("First message: {}", first_message);
@@ -91,10 +671,10 @@ impl ResponseMessage {
     pub fn first_message(&self) -> String {
         match self {
             ResponseMessage::Anthropic(response) => {
-                if let Some(content) = response.content.first() {
+                if let Some(content) = response.answer_content().next() {
                     match content {
                         AnthropicContentBlock::Text { text, .. } => text.clone(),
-                        AnthropicContentBlock::ToolUse { .. } => String::new(), // or handle tool use as needed
+                        _ => String::new(), // or handle tool use as needed
                     }
                 } else {
                     String::new()
@@ -118,9 +698,77 @@ impl ResponseMessage {
                     String::new()
                 }
             }
+            ResponseMessage::Gemini(response) => response.candidates.first()
+                .and_then(|candidate| candidate.content.parts.first())
+                .and_then(|part| part.text.clone())
+                .unwrap_or_default(),
+            ResponseMessage::Bedrock(response) => response.output.message.content.first()
+                .and_then(|block| block.text.clone())
+                .unwrap_or_default(),
+            ResponseMessage::Ollama(response) => response.message.content.clone(),
+        }
+    }
+
+    /// Returns all the text of the response, concatenating every text block in order.
+    ///
+    /// Unlike `first_message`, which only returns the first content block, this keeps the text
+    /// of Anthropic responses with several text blocks (e.g. around tool calls) and of Gemini
+    /// responses split into several parts. Only the first candidate is used; see
+    /// `messages_all` for the others.
+    pub fn text(&self) -> String {
+        self.content_blocks().into_iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text, .. } => Some(text),
+                ContentBlock::ToolUse { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Returns the text content of every generated candidate, one entry per OpenAI choice or
+    /// Gemini candidate (see `RequestBuilder::n`). Anthropic, Bedrock and Ollama responses always
+    /// contain a single candidate.
+    pub fn messages_all(&self) -> Vec<String> {
+        self.choices().into_iter().map(|choice| choice.text).collect()
+    }
+
+    /// Returns every generated candidate with its index and stop reason, one entry per OpenAI
+    /// choice or Gemini candidate (see `RequestBuilder::n`). Anthropic, Bedrock and Ollama
+    /// responses always contain a single candidate.
+    pub fn choices(&self) -> Vec<Choice> {
+        match self {
+            ResponseMessage::Anthropic(_) | ResponseMessage::Bedrock(_) | ResponseMessage::Ollama(_) =>
+                vec![Choice {
+                    index: 0,
+                    text: self.first_message(),
+                    stop_reason: self.normalized_stop_reason(),
+                    logprobs: None,
+                }],
+            ResponseMessage::OpenAI(response) => response.choices.iter()
+                .map(|choice| Choice {
+                    index: choice.index,
+                    text: choice.message.content.clone().unwrap_or_default(),
+                    stop_reason: StopReason::from(choice.finish_reason.as_deref().unwrap_or("")),
+                    logprobs: choice.logprobs.as_ref().and_then(|logprobs| logprobs.content.clone()),
+                })
+                .collect(),
+            ResponseMessage::Gemini(response) => response.candidates.iter()
+                .map(|candidate| Choice {
+                    index: candidate.index,
+                    text: candidate.content.parts.iter()
+                        .filter_map(|part| part.text.as_deref())
+                        .collect(),
+                    stop_reason: StopReason::from(candidate.finish_reason.as_deref().unwrap_or("")),
+                    logprobs: None,
+                })
+                .collect(),
         }
     }
 
+    /// Returns the tool calls in the response.
+    ///
+    /// Tool inputs are canonicalized so the same logical input compares equal regardless of
+    /// provider: whole-number floats such as `2.0` or `1e2` become integers. Object keys are
+    /// already kept in sorted order.
     pub fn tools(&self) -> Option<Vec<ToolResponse>> {
         match self {
             ResponseMessage::Anthropic(response) => {
@@ -130,7 +778,7 @@ impl ResponseMessage {
                             Some(ToolResponse {
                                 id: id.clone(),
                                 name: name.clone(),
-                                input: input.clone(),
+                                input: canonicalize_json(input.clone()),
                             })
                         } else {
                             None
@@ -146,15 +794,183 @@ impl ResponseMessage {
                     .map(|tool_call| ToolResponse {
                         id: tool_call.id.clone(),
                         name: tool_call.function.name.clone(),
-                        input: serde_json::from_str(&tool_call.function.arguments).unwrap_or(serde_json::Value::Null),
+                        input: serde_json::from_str(&tool_call.function.arguments)
+                            .map(canonicalize_json)
+                            .unwrap_or(serde_json::Value::Null),
                     })
                     .collect();
                 if tool_calls.is_empty() { None } else { Some(tool_calls) }
             },
+            ResponseMessage::Gemini(response) => {
+                let function_calls: Vec<ToolResponse> = response.candidates.iter()
+                    .flat_map(|candidate| &candidate.content.parts)
+                    .filter_map(|part| part.function_call.as_ref())
+                    .map(GeminiFunctionCall::to_tool_response)
+                    .collect();
+                if function_calls.is_empty() { None } else { Some(function_calls) }
+            },
+            ResponseMessage::Bedrock(response) => {
+                let tool_uses: Vec<ToolResponse> = response.output.message.content.iter()
+                    .filter_map(|block| block.tool_use.as_ref())
+                    .map(BedrockToolUse::to_tool_response)
+                    .collect();
+                if tool_uses.is_empty() { None } else { Some(tool_uses) }
+            },
+            ResponseMessage::Ollama(response) => {
+                let tool_calls: Vec<ToolResponse> = response.message.tool_calls.iter()
+                    .flatten()
+                    .map(OllamaToolCall::to_tool_response)
+                    .collect();
+                if tool_calls.is_empty() { None } else { Some(tool_calls) }
+            },
+        }
+    }
+
+    /// Returns every tool call in the response as a JSON array of `{"name", "input"}` objects,
+    /// which is convenient for compact audit logging. The array is empty when no tools were called.
+    pub fn tool_inputs_json(&self) -> serde_json::Value {
+        serde_json::Value::Array(
+            self.tools()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|tool| serde_json::json!({ "name": tool.name, "input": tool.input }))
+                .collect(),
+        )
+    }
+
+    /// Returns the content of the response as an ordered list of blocks.
+    ///
+    /// Each block carries its position in the response, matching the provider's own content
+    /// block index, so callers can keep rendered elements in sync with the response.
+    ///
+    /// OpenAI and Ollama messages hold text and tool calls in separate fields without specifying
+    /// how they interleave, so by convention the text block (if any) comes first, followed by one
+    /// tool-use block per tool call in the order they were returned.
+    pub fn content_blocks(&self) -> Vec<ContentBlock> {
+        match self {
+            ResponseMessage::Anthropic(response) => response.content.iter()
+                .enumerate()
+                .filter_map(|(index, block)| match block {
+                    AnthropicContentBlock::Text { text, .. } => Some(ContentBlock::Text {
+                        index,
+                        text: text.clone(),
+                    }),
+                    AnthropicContentBlock::ToolUse { id, name, input, .. } => Some(ContentBlock::ToolUse {
+                        index,
+                        tool: ToolResponse {
+                            id: id.clone(),
+                            name: name.clone(),
+                            input: canonicalize_json(input.clone()),
+                        },
+                    }),
+                    AnthropicContentBlock::Thinking { .. }
+                    | AnthropicContentBlock::RedactedThinking { .. }
+                    | AnthropicContentBlock::Unknown(_) => None,
+                })
+                .collect(),
+            ResponseMessage::OpenAI(response) => {
+                let mut blocks = Vec::new();
+                if let Some(text) = response.choices.first()
+                    .and_then(|choice| choice.message.content.clone())
+                    .filter(|text| !text.is_empty()) {
+                    blocks.push(ContentBlock::Text { index: 0, text });
+                }
+                for tool in self.tools().unwrap_or_default() {
+                    blocks.push(ContentBlock::ToolUse { index: blocks.len(), tool });
+                }
+                blocks
+            }
+            ResponseMessage::Gemini(response) => response.candidates.first()
+                .map(|candidate| candidate.content.parts.iter()
+                    .enumerate()
+                    .filter_map(|(index, part)| match (&part.text, &part.function_call) {
+                        (_, Some(function_call)) => Some(ContentBlock::ToolUse {
+                            index,
+                            tool: function_call.to_tool_response(),
+                        }),
+                        (Some(text), None) => Some(ContentBlock::Text { index, text: text.clone() }),
+                        (None, None) => None,
+                    })
+                    .collect())
+                .unwrap_or_default(),
+            ResponseMessage::Bedrock(response) => response.output.message.content.iter()
+                .enumerate()
+                .filter_map(|(index, block)| match (&block.text, &block.tool_use) {
+                    (_, Some(tool_use)) => Some(ContentBlock::ToolUse { index, tool: tool_use.to_tool_response() }),
+                    (Some(text), None) => Some(ContentBlock::Text { index, text: text.clone() }),
+                    (None, None) => None,
+                })
+                .collect(),
+            ResponseMessage::Ollama(response) => {
+                let mut blocks = Vec::new();
+                if !response.message.content.is_empty() {
+                    blocks.push(ContentBlock::Text { index: 0, text: response.message.content.clone() });
+                }
+                for tool in self.tools().unwrap_or_default() {
+                    blocks.push(ContentBlock::ToolUse { index: blocks.len(), tool });
+                }
+                blocks
+            }
+        }
+    }
+
+    /// Converts the response into an assistant `Message` that can be replayed in a follow-up
+    /// request, preserving any tool calls in the provider's native format.
+    pub fn to_assistant_message(&self) -> Message {
+        match self {
+            ResponseMessage::Anthropic(response) => Message {
+                role: Role::Assistant,
+                content: serde_json::to_value(&response.content).unwrap_or_default(),
+                ..Default::default()
+            },
+            ResponseMessage::OpenAI(response) => {
+                let message = response.choices.first().map(|choice| &choice.message);
+                Message {
+                    role: Role::Assistant,
+                    content: message
+                        .and_then(|message| message.content.clone())
+                        .map(serde_json::Value::String)
+                        .unwrap_or_default(),
+                    tool_calls: message.and_then(|message| message.tool_calls.clone()),
+                    ..Default::default()
+                }
+            }
+            ResponseMessage::Gemini(response) => Message {
+                role: Role::Assistant,
+                content: response.candidates.first()
+                    .and_then(|candidate| serde_json::to_value(&candidate.content.parts).ok())
+                    .unwrap_or_default(),
+                ..Default::default()
+            },
+            ResponseMessage::Bedrock(response) => Message {
+                role: Role::Assistant,
+                content: serde_json::to_value(&response.output.message.content).unwrap_or_default(),
+                ..Default::default()
+            },
+            // Tool calls are stored in OpenAI form; the Ollama request renderer converts them back.
+            ResponseMessage::Ollama(response) => Message {
+                role: Role::Assistant,
+                content: response.message.content.clone().into(),
+                tool_calls: response.message.tool_calls.as_ref().map(|tool_calls| tool_calls.iter()
+                    .map(|tool_call| OpenAIToolCall {
+                        id: tool_call.function.name.clone(),
+                        call_type: "function".to_string(),
+                        function: OpenAIFunction {
+                            name: tool_call.function.name.clone(),
+                            arguments: tool_call.function.arguments.to_string(),
+                        },
+                    })
+                    .collect()),
+                ..Default::default()
+            },
         }
     }
 
-    /// Returns the role of the sender in the response.
+    /// Returns the role of the sender in the response, exactly as the provider reported it.
+    ///
+    /// This stays a `&str` so provider-specific names such as Gemini's `model` are not lost, and
+    /// is empty when the response carries no role. Use `normalized_role` to compare against a
+    /// `Role`.
     ///
     /// # Examples
     ///
@@ -167,7 +983,10 @@ impl ResponseMessage {
     ///     model: "".to_string(),
     ///     stop_reason: "".to_string(),
     ///     stop_sequence: None,
-    ///     usage: Default::default(),}
+    ///     usage: Default::default(),
+    ///     extra: Default::default(),
+    ///     raw_body: None,
+    ///     meta: None,}
     /// );
     /// let role = response.role();
     /// println!("Role: {}", role);
@@ -182,9 +1001,21 @@ impl ResponseMessage {
                     ""
                 }
             }
+            ResponseMessage::Gemini(response) => response.candidates.first()
+                .map(|candidate| candidate.content.role.as_str())
+                .unwrap_or(""),
+            ResponseMessage::Bedrock(response) => &response.output.message.role,
+            ResponseMessage::Ollama(response) => &response.message.role,
         }
     }
 
+    /// Returns the role of the response normalized across providers, so Gemini's `model` is
+    /// `Role::Assistant` like every other provider's responses. Responses without a role (such as
+    /// an OpenAI response with no choices) are treated as assistant turns.
+    pub fn normalized_role(&self) -> Role {
+        Role::from_provider(self.role()).unwrap_or(Role::Assistant)
+    }
+
     /// Returns the name of the model used for generating the response.
     ///
     /// # Examples
@@ -198,7 +1029,10 @@ impl ResponseMessage {
     ///     model: "".to_string(),
     ///     stop_reason: "".to_string(),
     ///     stop_sequence: None,
-    ///     usage: Default::default(),}
+    ///     usage: Default::default(),
+    ///     extra: Default::default(),
+    ///     raw_body: None,
+    ///     meta: None,}
     /// );
     /// let model = response.model();
     /// println!("Model: {}", model);
@@ -207,6 +1041,34 @@ impl ResponseMessage {
         match self {
             ResponseMessage::Anthropic(response) => &response.model,
             ResponseMessage::OpenAI(response) => &response.model,
+            ResponseMessage::Gemini(response) => response.model_version.as_deref().unwrap_or(""),
+            // The Converse response doesn't echo the model id.
+            ResponseMessage::Bedrock(_) => "",
+            ResponseMessage::Ollama(response) => &response.model,
+        }
+    }
+
+    /// Returns the provider-assigned response id, e.g. for logging or deduplication.
+    pub fn id(&self) -> &str {
+        match self {
+            ResponseMessage::Anthropic(response) => &response.id,
+            ResponseMessage::OpenAI(response) => &response.id,
+            ResponseMessage::Gemini(response) => response.response_id.as_deref().unwrap_or(""),
+            // Bedrock only returns the request id as the `x-amzn-RequestId` header.
+            ResponseMessage::Bedrock(_) => "",
+            // Ollama responses carry no id.
+            ResponseMessage::Ollama(_) => "",
+        }
+    }
+
+    /// Returns the Unix timestamp (in seconds) at which the response was created.
+    ///
+    /// Only OpenAI reports a creation time; other providers always return `None`.
+    pub fn created_at(&self) -> Option<i64> {
+        match self {
+            ResponseMessage::Anthropic(_) | ResponseMessage::Gemini(_) | ResponseMessage::Bedrock(_)
+            | ResponseMessage::Ollama(_) => None,
+            ResponseMessage::OpenAI(response) => Some(response.created),
         }
     }
 
@@ -223,7 +1085,10 @@ impl ResponseMessage {
     ///     model: "".to_string(),
     ///     stop_reason: "".to_string(),
     ///     stop_sequence: None,
-    ///     usage: Default::default(),}
+    ///     usage: Default::default(),
+    ///     extra: Default::default(),
+    ///     raw_body: None,
+    ///     meta: None,}
     /// );
     /// let stop_reason = response.stop_reason();
     /// println!("Stop reason: {}", stop_reason);
@@ -232,13 +1097,136 @@ impl ResponseMessage {
         match self {
             ResponseMessage::Anthropic(response) => &response.stop_reason,
             ResponseMessage::OpenAI(response) => {
-                if let Some(choice) = response.choices.first() {
-                    &choice.finish_reason
-                } else {
-                    ""
-                }
+                response.choices.first()
+                    .and_then(|choice| choice.finish_reason.as_deref())
+                    .unwrap_or("")
+            }
+            ResponseMessage::Gemini(response) => response.candidates.first()
+                .and_then(|candidate| candidate.finish_reason.as_deref())
+                .unwrap_or(""),
+            ResponseMessage::Bedrock(response) => &response.stop_reason,
+            ResponseMessage::Ollama(response) => response.done_reason.as_deref().unwrap_or(""),
+        }
+    }
+
+    /// Returns the stop reason normalized across providers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use llm_bridge::response::{AnthropicResponse, ResponseMessage, StopReason};
+    /// let response = ResponseMessage::Anthropic(AnthropicResponse {
+    ///     id: "".to_string(),
+    ///     role: "".to_string(),
+    ///     content: vec![],
+    ///     model: "".to_string(),
+    ///     stop_reason: "max_tokens".to_string(),
+    ///     stop_sequence: None,
+    ///     usage: Default::default(),
+    ///     extra: Default::default(),
+    ///     raw_body: None,
+    ///     meta: None,}
+    /// );
+    /// assert_eq!(response.normalized_stop_reason(), StopReason::Length);
+    /// ```
+    pub fn normalized_stop_reason(&self) -> StopReason {
+        StopReason::from(self.stop_reason())
+    }
+
+    /// Returns the reasoning (chain of thought) that preceded the answer, as returned in the
+    /// `reasoning_content` field of DeepSeek reasoning models or the first `thinking` block of
+    /// Anthropic extended thinking. `first_message` only returns the final answer. Always `None`
+    /// for other providers.
+    pub fn reasoning_content(&self) -> Option<&str> {
+        match self {
+            ResponseMessage::OpenAI(response) => response.choices.first()
+                .and_then(|choice| choice.message.reasoning_content.as_deref()),
+            ResponseMessage::Anthropic(response) => response.content.iter()
+                .find_map(|block| match block {
+                    AnthropicContentBlock::Thinking { thinking, .. } => Some(thinking.as_str()),
+                    _ => None,
+                }),
+            ResponseMessage::Gemini(_) | ResponseMessage::Bedrock(_) | ResponseMessage::Ollama(_) => None,
+        }
+    }
+
+    /// Returns the URLs of the sources cited by a Perplexity response. Always `None` for other
+    /// providers.
+    pub fn citations(&self) -> Option<&[String]> {
+        match self {
+            ResponseMessage::OpenAI(response) => response.citations.as_deref(),
+            ResponseMessage::Anthropic(_) | ResponseMessage::Gemini(_) | ResponseMessage::Bedrock(_)
+            | ResponseMessage::Ollama(_) => None,
+        }
+    }
+
+    /// Returns the web search results a Perplexity response is grounded on, with titles and
+    /// dates alongside the URLs. Always `None` for other providers.
+    pub fn search_results(&self) -> Option<&[SearchResult]> {
+        match self {
+            ResponseMessage::OpenAI(response) => response.search_results.as_deref(),
+            ResponseMessage::Anthropic(_) | ResponseMessage::Gemini(_) | ResponseMessage::Bedrock(_)
+            | ResponseMessage::Ollama(_) => None,
+        }
+    }
+
+    /// Returns the OpenAI `system_fingerprint`, which identifies the backend configuration that
+    /// served the request. Useful alongside `RequestBuilder::seed` to detect changes that may
+    /// affect determinism. Always `None` for other providers.
+    pub fn system_fingerprint(&self) -> Option<&str> {
+        match self {
+            ResponseMessage::Anthropic(_) | ResponseMessage::Gemini(_) | ResponseMessage::Bedrock(_)
+            | ResponseMessage::Ollama(_) => None,
+            ResponseMessage::OpenAI(response) => response.system_fingerprint.as_deref(),
+        }
+    }
+
+    /// Returns the per-token log probabilities of the first choice, if they were requested with
+    /// `RequestBuilder::logprobs`. Always `None` for other providers.
+    pub fn logprobs(&self) -> Option<&[TokenLogprob]> {
+        match self {
+            ResponseMessage::Anthropic(_) | ResponseMessage::Gemini(_) | ResponseMessage::Bedrock(_)
+            | ResponseMessage::Ollama(_) => None,
+            ResponseMessage::OpenAI(response) => response.choices.first()
+                .and_then(|choice| choice.logprobs.as_ref())
+                .and_then(|logprobs| logprobs.content.as_deref()),
+        }
+    }
+
+    /// Returns the custom stop sequence that ended generation, if any.
+    ///
+    /// Only Anthropic reports which stop sequence was matched; other providers always return `None`.
+    pub fn matched_stop_sequence(&self) -> Option<&str> {
+        match self {
+            ResponseMessage::Anthropic(response) => response.stop_sequence.as_deref(),
+            ResponseMessage::OpenAI(_) | ResponseMessage::Gemini(_) | ResponseMessage::Bedrock(_)
+            | ResponseMessage::Ollama(_) => None,
+        }
+    }
+
+    /// Converts content-filter stops and refusals into `ApiError::Rejected`, so strict pipelines
+    /// can use `?` to skip past unacceptable outcomes. Any other response is returned unchanged.
+    pub fn as_result(self) -> Result<ResponseMessage, ApiError> {
+        if let ResponseMessage::OpenAI(response) = &self {
+            if let Some(refusal) = response.choices.iter().find_map(|choice| choice.message.refusal.as_ref()) {
+                return Err(ApiError::Rejected(format!("refusal: {}", refusal)));
+            }
+        }
+        if let ResponseMessage::Gemini(response) = &self {
+            if let Some(block_reason) = response.prompt_feedback.as_ref().and_then(|feedback| feedback.block_reason.as_ref()) {
+                return Err(ApiError::Rejected(format!("prompt blocked: {}", block_reason)));
             }
         }
+        match self.normalized_stop_reason() {
+            StopReason::ContentFilter => Err(ApiError::Rejected("content filtered".to_string())),
+            _ => Ok(self),
+        }
+    }
+
+    /// Returns `true` when generation stopped because it hit the token limit (Anthropic
+    /// `max_tokens`, OpenAI `length`), i.e. the response is incomplete and may need continuing.
+    pub fn was_truncated(&self) -> bool {
+        self.normalized_stop_reason() == StopReason::Length
     }
 
     /// Returns the usage information for the generated response.
@@ -254,7 +1242,10 @@ impl ResponseMessage {
     ///     model: "".to_string(),
     ///     stop_reason: "".to_string(),
     ///     stop_sequence: None,
-    ///     usage: Default::default(),}
+    ///     usage: Default::default(),
+    ///     extra: Default::default(),
+    ///     raw_body: None,
+    ///     meta: None,}
     /// );
     /// let usage = response.usage();
     /// println!("Input tokens: {}", usage.input_tokens);
@@ -265,14 +1256,52 @@ impl ResponseMessage {
             ResponseMessage::Anthropic(response) => CommonUsage {
                 input_tokens: response.usage.input_tokens,
                 output_tokens: response.usage.output_tokens,
+                cache_read_input_tokens: response.usage.cache_read_input_tokens,
+                cache_creation_input_tokens: response.usage.cache_creation_input_tokens,
+                ..Default::default()
             },
-            ResponseMessage::OpenAI(response) => CommonUsage {
-                input_tokens: response.usage.prompt_tokens,
-                output_tokens: response.usage.completion_tokens,
+            ResponseMessage::OpenAI(response) => CommonUsage::from(&response.usage),
+            ResponseMessage::Gemini(response) => CommonUsage {
+                input_tokens: response.usage_metadata.prompt_token_count,
+                // Gemini counts thoughts separately, but bills them as output.
+                output_tokens: response.usage_metadata.candidates_token_count
+                    + response.usage_metadata.thoughts_token_count.unwrap_or(0),
+                cache_read_input_tokens: response.usage_metadata.cached_content_token_count,
+                reasoning_tokens: response.usage_metadata.thoughts_token_count,
+                ..Default::default()
+            },
+            ResponseMessage::Bedrock(response) => CommonUsage {
+                input_tokens: response.usage.input_tokens,
+                output_tokens: response.usage.output_tokens,
+                cache_read_input_tokens: response.usage.cache_read_input_tokens,
+                cache_creation_input_tokens: response.usage.cache_write_input_tokens,
+                ..Default::default()
+            },
+            ResponseMessage::Ollama(response) => CommonUsage {
+                input_tokens: response.prompt_eval_count,
+                output_tokens: response.eval_count,
+                ..Default::default()
             },
         }
     }
-}
+
+    /// Returns the estimated cost of the response in USD, priced by the model that served it.
+    /// `None` if the model isn't in the `pricing` registry.
+    pub fn cost(&self) -> Option<f64> {
+        let provider = match self {
+            ResponseMessage::Anthropic(_) => ClientLlm::Anthropic,
+            ResponseMessage::OpenAI(_) => ClientLlm::OpenAI,
+            ResponseMessage::Gemini(_) => ClientLlm::Gemini,
+            ResponseMessage::Ollama(_) => ClientLlm::Ollama,
+            #[cfg(feature = "bedrock")]
+            ResponseMessage::Bedrock(_) => ClientLlm::Bedrock,
+            // Bedrock responses are only produced with the `bedrock` feature.
+            #[cfg(not(feature = "bedrock"))]
+            ResponseMessage::Bedrock(_) => return None,
+        };
+        self.usage().cost(&provider, self.model())
+    }
+}
 
 impl fmt::Display for ResponseMessage {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -291,29 +1320,182 @@ impl fmt::Display for ResponseMessage {
                     response.id, response.object, response.model, response.choices
                 )
             }
+            ResponseMessage::Gemini(response) => {
+                write!(
+                    f,
+                    "ResponseMessage {{ id: {}, model: {}, candidates: {:?} }}",
+                    response.response_id.as_deref().unwrap_or(""),
+                    response.model_version.as_deref().unwrap_or(""),
+                    response.candidates
+                )
+            }
+            ResponseMessage::Bedrock(response) => {
+                write!(
+                    f,
+                    "ResponseMessage {{ role: {}, content: {:?} }}",
+                    response.output.message.role, response.output.message.content
+                )
+            }
+            ResponseMessage::Ollama(response) => {
+                write!(
+                    f,
+                    "ResponseMessage {{ model: {}, message: {:?} }}",
+                    response.model, response.message
+                )
+            }
         }
     }
 }
 
 
+/// Normalizes numbers in `value` so that whole-number floats are represented as integers.
+fn canonicalize_json(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Number(number) => match number.as_f64() {
+            Some(float) if number.is_f64() && float.fract() == 0.0 && float.abs() < i64::MAX as f64 =>
+                serde_json::Value::from(float as i64),
+            _ => serde_json::Value::Number(number),
+        },
+        serde_json::Value::Array(items) =>
+            serde_json::Value::Array(items.into_iter().map(canonicalize_json).collect()),
+        serde_json::Value::Object(map) =>
+            serde_json::Value::Object(map.into_iter().map(|(key, value)| (key, canonicalize_json(value))).collect()),
+        other => other,
+    }
+}
+
+/// The reason the model stopped generating, normalized across providers.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    /// The model reached a natural stopping point (Anthropic `end_turn`, OpenAI `stop`, Gemini `STOP`).
+    EndTurn,
+    /// The token limit was reached (Anthropic `max_tokens`, OpenAI `length`, Gemini `MAX_TOKENS`).
+    Length,
+    /// The model requested one or more tool calls (Anthropic `tool_use`, OpenAI `tool_calls`).
+    ToolUse,
+    /// One of the custom stop sequences was generated (Anthropic `stop_sequence`).
+    StopSequence,
+    /// Content was omitted or declined for safety reasons (OpenAI `content_filter`, Anthropic
+    /// `refusal`, Gemini `SAFETY` and related reasons, Bedrock
+    /// `content_filtered`/`guardrail_intervened`).
+    ContentFilter,
+    /// Any other provider-specific stop reason.
+    Other(String),
+}
+
+impl From<&str> for StopReason {
+    fn from(stop_reason: &str) -> Self {
+        match stop_reason {
+            "end_turn" | "stop" | "STOP" => StopReason::EndTurn,
+            "max_tokens" | "length" | "MAX_TOKENS" => StopReason::Length,
+            "tool_use" | "tool_calls" | "function_call" => StopReason::ToolUse,
+            "stop_sequence" => StopReason::StopSequence,
+            "content_filter" | "content_filtered" | "guardrail_intervened" | "refusal"
+            | "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" => StopReason::ContentFilter,
+            other => StopReason::Other(other.to_string()),
+        }
+    }
+}
+
+/// One generated candidate, as returned by `ResponseMessage::choices`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Choice {
+    pub index: usize,
+    pub text: String,
+    pub stop_reason: StopReason,
+    /// Per-token log probabilities of this candidate, if requested with
+    /// `RequestBuilder::logprobs`. Only returned by OpenAI.
+    pub logprobs: Option<Vec<TokenLogprob>>,
+}
+
 /// Tokens represent the underlying cost to llm systems.
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct AnthropicUsage {
     pub input_tokens: usize,
     pub output_tokens: usize,
+    /// Input tokens read from the prompt cache, which are not included in `input_tokens`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<usize>,
+    /// Input tokens written to the prompt cache, which are not included in `input_tokens`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input_tokens: Option<usize>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+/// Token usage normalized across providers. The detailed counts are `None` when the provider
+/// doesn't report them.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct CommonUsage {
     pub input_tokens: usize,
     pub output_tokens: usize,
+    /// Input tokens read from the provider's prompt cache. Anthropic and Bedrock count these
+    /// separately from `input_tokens`; OpenAI and Gemini include them in it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<usize>,
+    /// Input tokens written to the prompt cache (Anthropic and Bedrock only), not included in
+    /// `input_tokens`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input_tokens: Option<usize>,
+    /// Output tokens spent on reasoning, included in `output_tokens`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_tokens: Option<usize>,
+    /// Audio input and output tokens (OpenAI only), included in the totals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_tokens: Option<usize>,
+}
+
+impl CommonUsage {
+    /// Returns the estimated cost in USD of this usage of `model`, as reported by `provider`, or
+    /// `None` if the model isn't in the `pricing` registry. See `ModelPrice::cost`.
+    pub fn cost(&self, provider: &ClientLlm, model: &str) -> Option<f64> {
+        pricing::price(model).map(|price| price.cost(self, provider))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct OpenAIChoice {
     pub index: usize,
     pub message: OpenAIMessage,
-    pub finish_reason: String,
+    #[serde(default)]
+    pub logprobs: Option<OpenAILogprobs>,
+    /// Omitted by some proxies and streaming final payloads.
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// Log probability information for a choice, present when requested with
+/// `RequestBuilder::logprobs`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OpenAILogprobs {
+    pub content: Option<Vec<TokenLogprob>>,
+}
+
+/// The log probability of a generated token, with the most likely alternatives if requested.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TokenLogprob {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default)]
+    pub bytes: Option<Vec<u8>>,
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+/// A web page returned by Perplexity's search.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+/// One of the most likely tokens at a position in the output.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default)]
+    pub bytes: Option<Vec<u8>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -321,6 +1503,35 @@ pub struct OpenAIMessage {
     pub role: String,
     pub content: Option<String>,
     pub tool_calls: Option<Vec<OpenAIToolCall>>,
+    /// The model's explanation when it declines to respond.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refusal: Option<String>,
+    /// The chain of thought returned by reasoning models such as `deepseek-reasoner`, separate
+    /// from the final answer in `content`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+}
+
+/// A single block of response content, tagged with its index within the response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ContentBlock {
+    Text {
+        index: usize,
+        text: String,
+    },
+    ToolUse {
+        index: usize,
+        tool: ToolResponse,
+    },
+}
+
+impl ContentBlock {
+    /// Returns the position of this block within the response content.
+    pub fn index(&self) -> usize {
+        match self {
+            ContentBlock::Text { index, .. } | ContentBlock::ToolUse { index, .. } => *index,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -331,7 +1542,7 @@ pub struct ToolResponse {
 }
 
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OpenAIToolCall {
     pub id: String,
     #[serde(rename = "type")]
@@ -339,19 +1550,182 @@ pub struct OpenAIToolCall {
     pub function: OpenAIFunction,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OpenAIFunction {
     pub name: String,
+    /// The JSON-encoded arguments. Servers that return the arguments as a JSON object, such as
+    /// Hugging Face TGI, are accepted and re-encoded.
+    #[serde(deserialize_with = "deserialize_arguments")]
     pub arguments: String,
 }
 
-#[cfg(test)]
+fn deserialize_arguments<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(arguments) => Ok(arguments),
+        arguments => Ok(arguments.to_string()),
+    }
+}
+
+/// Represents the response body returned by the OpenAI embeddings API.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OpenAIEmbeddingResponse {
+    pub object: String,
+    pub data: Vec<OpenAIEmbedding>,
+    pub model: String,
+    pub usage: OpenAIEmbeddingUsage,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OpenAIEmbedding {
+    pub object: String,
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct OpenAIEmbeddingUsage {
+    pub prompt_tokens: usize,
+    pub total_tokens: usize,
+}
+
+/// The vector embeddings created for a list of inputs, in the same order as the inputs.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct EmbeddingResponse {
+    pub embeddings: Vec<Vec<f32>>,
+    pub usage: CommonUsage,
+}
 
+impl From<OpenAIEmbeddingResponse> for EmbeddingResponse {
+    fn from(mut response: OpenAIEmbeddingResponse) -> Self {
+        response.data.sort_by_key(|embedding| embedding.index);
+        EmbeddingResponse {
+            embeddings: response.data.into_iter().map(|embedding| embedding.embedding).collect(),
+            usage: CommonUsage {
+                input_tokens: response.usage.prompt_tokens,
+                output_tokens: 0,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
     use crate::response::{AnthropicContentBlock, AnthropicResponse};
 
+    #[test]
+    fn test_unknown_fields_and_blocks() {
+        let response: ResponseMessage = serde_json::from_value(json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "content": [
+                {"type": "server_tool_use", "id": "srvtoolu_1", "name": "web_search", "input": {"query": "weather"}},
+                {"type": "web_search_tool_result", "tool_use_id": "srvtoolu_1", "content": [], "text": "ignored"},
+                {"type": "text", "text": "It is sunny."}
+            ],
+            "model": "claude-3-5-sonnet-20240620",
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 10, "output_tokens": 5},
+            "container": {"id": "container_1"}
+        })).unwrap();
+
+        let ResponseMessage::Anthropic(anthropic) = &response else { panic!("expected an Anthropic response") };
+        assert!(matches!(&anthropic.content[0], AnthropicContentBlock::Unknown(block) if block["type"] == "server_tool_use"));
+        assert!(matches!(&anthropic.content[1], AnthropicContentBlock::Unknown(block) if block["type"] == "web_search_tool_result"));
+        assert_eq!(anthropic.extra["container"], json!({"id": "container_1"}));
+        assert_eq!(response.first_message(), "It is sunny.");
+        assert!(response.tools().is_none());
+        assert_eq!(serde_json::to_value(&response).unwrap()["content"][1]["tool_use_id"], "srvtoolu_1");
+    }
+
+    #[test]
+    fn test_detailed_usage() {
+        let anthropic: AnthropicResponse = serde_json::from_value(json!({
+            "id": "msg_1",
+            "role": "assistant",
+            "content": [],
+            "model": "claude-3-5-sonnet-20240620",
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 10, "output_tokens": 5, "cache_read_input_tokens": 1200, "cache_creation_input_tokens": 0}
+        })).unwrap();
+        let usage = ResponseMessage::Anthropic(anthropic).usage();
+        assert_eq!(usage.cache_read_input_tokens, Some(1200));
+        assert_eq!(usage.cache_creation_input_tokens, Some(0));
+        assert_eq!(usage.reasoning_tokens, None);
+
+        let bedrock: BedrockResponse = serde_json::from_value(json!({
+            "output": {"message": {"role": "assistant", "content": [{"text": "Hello!"}]}},
+            "stopReason": "end_turn",
+            "usage": {"inputTokens": 10, "outputTokens": 5, "totalTokens": 2015,
+                      "cacheReadInputTokens": 1500, "cacheWriteInputTokens": 500}
+        })).unwrap();
+        let usage = ResponseMessage::Bedrock(bedrock).usage();
+        assert_eq!(usage.cache_read_input_tokens, Some(1500));
+        assert_eq!(usage.cache_creation_input_tokens, Some(500));
+
+        let openai: OpenAIResponse = serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "o3-mini",
+            "choices": [],
+            "usage": {
+                "prompt_tokens": 2000,
+                "completion_tokens": 300,
+                "total_tokens": 2300,
+                "prompt_tokens_details": {"cached_tokens": 1024, "audio_tokens": 0},
+                "completion_tokens_details": {"reasoning_tokens": 256, "audio_tokens": 0}
+            }
+        })).unwrap();
+        let openai = ResponseMessage::OpenAI(openai);
+        assert!((openai.cost().unwrap() - 0.0029568).abs() < 1e-12);
+        assert_eq!(openai.usage(), CommonUsage {
+            input_tokens: 2000,
+            output_tokens: 300,
+            cache_read_input_tokens: Some(1024),
+            cache_creation_input_tokens: None,
+            reasoning_tokens: Some(256),
+            audio_tokens: Some(0),
+        });
+    }
+
+    #[test]
+    fn test_rate_limit_info_from_headers() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(RateLimitInfo::from_headers(&headers), None);
+
+        headers.insert("anthropic-ratelimit-requests-limit", "50".parse().unwrap());
+        headers.insert("anthropic-ratelimit-requests-remaining", "49".parse().unwrap());
+        headers.insert("anthropic-ratelimit-requests-reset", "2024-07-01T12:00:00Z".parse().unwrap());
+        headers.insert("anthropic-ratelimit-output-tokens-remaining", "8000".parse().unwrap());
+        let info = RateLimitInfo::from_headers(&headers).unwrap();
+        assert_eq!(info.requests, RateLimit {
+            limit: Some(50),
+            remaining: Some(49),
+            reset: Some("2024-07-01T12:00:00Z".to_string()),
+        });
+        assert_eq!(info.output_tokens.remaining, Some(8000));
+        assert_eq!(info.tokens, RateLimit::default());
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-ratelimit-limit-tokens", "150000".parse().unwrap());
+        headers.insert("x-ratelimit-remaining-tokens", "149984".parse().unwrap());
+        headers.insert("x-ratelimit-reset-tokens", "6m0s".parse().unwrap());
+        let info = RateLimitInfo::from_headers(&headers).unwrap();
+        assert_eq!(info.tokens.limit, Some(150000));
+        assert_eq!(info.tokens.remaining, Some(149984));
+        assert_eq!(info.tokens.reset.as_deref(), Some("6m0s"));
+        assert_eq!(info.requests, RateLimit::default());
+    }
+
     #[test]
     fn test_anthropic_response_deserialization() {
         let json_response = json!({
@@ -397,6 +1771,10 @@ mod tests {
         } else {
             panic!("Expected ToolUse content block");
         }
+
+        let message = ResponseMessage::Anthropic(response);
+        assert_eq!(message.id(), "msg_01KGgxCr7Lm9gi1kfaZWWJUs");
+        assert_eq!(message.created_at(), None);
     }
 
     #[test]
@@ -533,7 +1911,7 @@ mod tests {
         assert_eq!(response.choices.len(), 1);
         let choice = &response.choices[0];
         assert_eq!(choice.index, 0);
-        assert_eq!(choice.finish_reason, "tool_calls");
+        assert_eq!(choice.finish_reason.as_deref(), Some("tool_calls"));
 
         let message = &choice.message;
         assert_eq!(message.role, "assistant");
@@ -551,7 +1929,11 @@ mod tests {
         assert_eq!(response.usage.prompt_tokens, 106);
         assert_eq!(response.usage.completion_tokens, 17);
         assert_eq!(response.usage.total_tokens, 123);
+        assert_eq!(response.system_fingerprint.as_deref(), Some("fp_400f27fa1f"));
 
+        let message = ResponseMessage::OpenAI(response);
+        assert_eq!(message.id(), "chatcmpl-9p5LSmflVqlG0Gk6ryp14XHKbNah8");
+        assert_eq!(message.created_at(), Some(1721962302));
     }
 
     #[test]
@@ -635,4 +2017,838 @@ mod tests {
         assert_eq!(response_message.stop_reason(), "stop");
         assert_eq!(response_message.first_message(), "This is a regular response without tool calls.");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_openai_embedding_response_deserialization() {
+        let json_response = json!({
+            "object": "list",
+            "data": [
+                {
+                    "object": "embedding",
+                    "index": 1,
+                    "embedding": [0.5, -0.25, 0.125]
+                },
+                {
+                    "object": "embedding",
+                    "index": 0,
+                    "embedding": [0.0023064255, -0.009327292, 0.015797347]
+                }
+            ],
+            "model": "text-embedding-3-small",
+            "usage": {
+                "prompt_tokens": 8,
+                "total_tokens": 8
+            }
+        });
+
+        let response: OpenAIEmbeddingResponse = serde_json::from_value(json_response).unwrap();
+        assert_eq!(response.model, "text-embedding-3-small");
+        assert_eq!(response.data.len(), 2);
+
+        let embeddings: EmbeddingResponse = response.into();
+        assert_eq!(embeddings.embeddings.len(), 2);
+        assert_eq!(embeddings.embeddings[0], vec![0.0023064255, -0.009327292, 0.015797347]);
+        assert_eq!(embeddings.embeddings[1], vec![0.5, -0.25, 0.125]);
+        assert_eq!(embeddings.usage.input_tokens, 8);
+        assert_eq!(embeddings.usage.output_tokens, 0);
+    }
+
+    #[test]
+    fn test_content_block_indices() {
+        let json_response = json!({
+            "id": "msg_blocks_example",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-haiku-20240307",
+            "content": [
+                {"type": "text", "text": "Checking both cities."},
+                {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"location": "Paris"}},
+                {"type": "tool_use", "id": "toolu_2", "name": "get_weather", "input": {"location": "Rome"}}
+            ],
+            "stop_reason": "tool_use",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 50, "output_tokens": 60}
+        });
+
+        let response: ResponseMessage = serde_json::from_value(json_response).unwrap();
+        let blocks = response.content_blocks();
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks.iter().map(ContentBlock::index).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(blocks[0], ContentBlock::Text { index: 0, text: "Checking both cities.".to_string() });
+        match &blocks[2] {
+            ContentBlock::ToolUse { index, tool } => {
+                assert_eq!(*index, 2);
+                assert_eq!(tool.id, "toolu_2");
+            }
+            _ => panic!("Expected ToolUse content block"),
+        }
+    }
+
+    #[test]
+    fn test_tool_inputs_json() {
+        let json_response = json!({
+            "id": "chatcmpl-multi",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [
+                {
+                    "index": 0,
+                    "message": {
+                        "role": "assistant",
+                        "content": null,
+                        "tool_calls": [
+                            {
+                                "id": "call_1",
+                                "type": "function",
+                                "function": {"name": "get_weather", "arguments": "{\"location\":\"Paris\"}"}
+                            },
+                            {
+                                "id": "call_2",
+                                "type": "function",
+                                "function": {"name": "get_time", "arguments": "{\"timezone\":\"CET\"}"}
+                            }
+                        ]
+                    },
+                    "finish_reason": "tool_calls"
+                }
+            ],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 20, "total_tokens": 30}
+        });
+
+        let response = ResponseMessage::OpenAI(serde_json::from_value(json_response).unwrap());
+
+        assert_eq!(response.tool_inputs_json(), json!([
+            {"name": "get_weather", "input": {"location": "Paris"}},
+            {"name": "get_time", "input": {"timezone": "CET"}}
+        ]));
+    }
+
+    #[test]
+    fn test_tool_inputs_json_empty() {
+        let json_response = json!({
+            "id": "msg_text_example",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-haiku-20240307",
+            "content": [{"type": "text", "text": "No tools needed."}],
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 10, "output_tokens": 20}
+        });
+
+        let response: ResponseMessage = serde_json::from_value(json_response).unwrap();
+
+        assert_eq!(response.tool_inputs_json(), json!([]));
+    }
+
+    #[test]
+    fn test_was_truncated_anthropic() {
+        let json_response = json!({
+            "id": "msg_truncated",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-haiku-20240307",
+            "content": [{"type": "text", "text": "Once upon a"}],
+            "stop_reason": "max_tokens",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 10, "output_tokens": 3}
+        });
+
+        let response: ResponseMessage = serde_json::from_value(json_response).unwrap();
+
+        assert_eq!(response.normalized_stop_reason(), StopReason::Length);
+        assert!(response.was_truncated());
+    }
+
+    #[test]
+    fn test_was_truncated_openai() {
+        let json_response = json!({
+            "id": "chatcmpl-truncated",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [
+                {
+                    "index": 0,
+                    "message": {"role": "assistant", "content": "Once upon a"},
+                    "finish_reason": "length"
+                }
+            ],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 3, "total_tokens": 13}
+        });
+
+        let response = ResponseMessage::OpenAI(serde_json::from_value(json_response).unwrap());
+
+        assert_eq!(response.normalized_stop_reason(), StopReason::Length);
+        assert!(response.was_truncated());
+    }
+
+    #[test]
+    fn test_not_truncated() {
+        let json_response = json!({
+            "id": "chatcmpl-123",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [
+                {
+                    "index": 0,
+                    "message": {"role": "assistant", "content": "Done."},
+                    "finish_reason": "stop"
+                }
+            ],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 2, "total_tokens": 12}
+        });
+
+        let response = ResponseMessage::OpenAI(serde_json::from_value(json_response).unwrap());
+
+        assert_eq!(response.normalized_stop_reason(), StopReason::EndTurn);
+        assert!(!response.was_truncated());
+    }
+
+    #[test]
+    fn test_matched_stop_sequence() {
+        let json_response = json!({
+            "id": "msg_stop_sequence",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-haiku-20240307",
+            "content": [{"type": "text", "text": "1, 2, 3, 4"}],
+            "stop_reason": "stop_sequence",
+            "stop_sequence": ", 5",
+            "usage": {"input_tokens": 10, "output_tokens": 8}
+        });
+
+        let response: ResponseMessage = serde_json::from_value(json_response).unwrap();
+
+        assert_eq!(response.normalized_stop_reason(), StopReason::StopSequence);
+        assert_eq!(response.matched_stop_sequence(), Some(", 5"));
+    }
+
+    #[test]
+    fn test_openai_messages_all() {
+        let json_response = json!({
+            "id": "chatcmpl-n",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [
+                {"index": 0, "message": {"role": "assistant", "content": "First"}, "finish_reason": "stop"},
+                {"index": 1, "message": {"role": "assistant", "content": "Second"}, "finish_reason": "stop"},
+                {"index": 2, "message": {"role": "assistant", "content": "Third"}, "finish_reason": "stop"}
+            ],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 3, "total_tokens": 13}
+        });
+
+        let response = ResponseMessage::OpenAI(serde_json::from_value(json_response).unwrap());
+
+        assert_eq!(response.messages_all(), vec!["First", "Second", "Third"]);
+        assert_eq!(response.first_message(), "First");
+    }
+
+    #[test]
+    fn test_anthropic_thinking_blocks() {
+        let response: ResponseMessage = serde_json::from_value(json!({
+            "id": "msg_thinking",
+            "type": "message",
+            "role": "assistant",
+            "content": [
+                {"type": "thinking", "thinking": "17 * 3 is 51.", "signature": "EqQBCgIYAhIM"},
+                {"type": "redacted_thinking", "data": "EmwKAhgBEgy3va3p"},
+                {"type": "text", "text": "51"}
+            ],
+            "model": "claude-3-7-sonnet-20250219",
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 20, "output_tokens": 30}
+        })).unwrap();
+
+        assert_eq!(response.first_message(), "51");
+        assert_eq!(response.reasoning_content(), Some("17 * 3 is 51."));
+        assert_eq!(response.content_blocks(), vec![ContentBlock::Text { index: 2, text: "51".to_string() }]);
+        assert_eq!(response.to_assistant_message().content[0]["signature"], "EqQBCgIYAhIM");
+    }
+
+    #[test]
+    fn test_text_concatenates_blocks() {
+        let response: ResponseMessage = serde_json::from_value(json!({
+            "id": "msg_blocks",
+            "type": "message",
+            "role": "assistant",
+            "content": [
+                {"type": "text", "text": "Let me check. "},
+                {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"location": "Paris"}},
+                {"type": "text", "text": "It is sunny."}
+            ],
+            "model": "claude-3-haiku-20240307",
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 20, "output_tokens": 30}
+        })).unwrap();
+
+        assert_eq!(response.first_message(), "Let me check. ");
+        assert_eq!(response.text(), "Let me check. It is sunny.");
+    }
+
+    #[test]
+    fn test_openai_choices() {
+        let json_response = json!({
+            "id": "chatcmpl-n",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [
+                {"index": 0, "message": {"role": "assistant", "content": "One"}, "finish_reason": "stop"},
+                {"index": 1, "message": {"role": "assistant", "content": "Tw"}, "finish_reason": "length"}
+            ],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 2, "total_tokens": 12}
+        });
+
+        let response = ResponseMessage::OpenAI(serde_json::from_value(json_response).unwrap());
+        let choices = response.choices();
+
+        assert_eq!(choices.len(), 2);
+        assert_eq!(choices[0].text, "One");
+        assert_eq!(choices[0].stop_reason, StopReason::EndTurn);
+        assert_eq!(choices[1].index, 1);
+        assert_eq!(choices[1].text, "Tw");
+        assert_eq!(choices[1].stop_reason, StopReason::Length);
+    }
+
+    #[test]
+    fn test_openai_content_blocks_text_then_tools() {
+        let json_response = json!({
+            "id": "chatcmpl-mixed",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [
+                {
+                    "index": 0,
+                    "message": {
+                        "role": "assistant",
+                        "content": "Let me look that up.",
+                        "tool_calls": [
+                            {
+                                "id": "call_1",
+                                "type": "function",
+                                "function": {"name": "get_weather", "arguments": "{\"location\":\"Paris\"}"}
+                            },
+                            {
+                                "id": "call_2",
+                                "type": "function",
+                                "function": {"name": "get_time", "arguments": "{\"timezone\":\"CET\"}"}
+                            }
+                        ]
+                    },
+                    "finish_reason": "tool_calls"
+                }
+            ],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 20, "total_tokens": 30}
+        });
+
+        let response = ResponseMessage::OpenAI(serde_json::from_value(json_response).unwrap());
+        let blocks = response.content_blocks();
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0], ContentBlock::Text { index: 0, text: "Let me look that up.".to_string() });
+        match (&blocks[1], &blocks[2]) {
+            (ContentBlock::ToolUse { index: 1, tool: first }, ContentBlock::ToolUse { index: 2, tool: second }) => {
+                assert_eq!(first.id, "call_1");
+                assert_eq!(second.id, "call_2");
+            }
+            _ => panic!("Expected the text block followed by two tool use blocks"),
+        }
+    }
+
+    #[test]
+    fn test_openai_response_missing_usage() {
+        let json_response = json!({
+            "id": "chatcmpl-no-usage",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [
+                {"index": 0, "message": {"role": "assistant", "content": "Hi"}, "finish_reason": "stop"}
+            ]
+        });
+
+        let response: OpenAIResponse = serde_json::from_value(json_response).unwrap();
+        let response_message = ResponseMessage::OpenAI(response);
+
+        assert_eq!(response_message.usage().input_tokens, 0);
+        assert_eq!(response_message.usage().output_tokens, 0);
+        assert_eq!(response_message.stop_reason(), "stop");
+    }
+
+    #[test]
+    fn test_openai_response_missing_finish_reason() {
+        let json_response = json!({
+            "id": "chatcmpl-no-finish",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [
+                {"index": 0, "message": {"role": "assistant", "content": "Hi"}}
+            ],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6}
+        });
+
+        let response: OpenAIResponse = serde_json::from_value(json_response).unwrap();
+        assert_eq!(response.choices[0].finish_reason, None);
+
+        let response_message = ResponseMessage::OpenAI(response);
+        assert_eq!(response_message.stop_reason(), "");
+        assert_eq!(response_message.first_message(), "Hi");
+    }
+
+    #[test]
+    fn test_as_result_openai_refusal() {
+        let response: ResponseMessage = serde_json::from_value(json!({
+            "id": "chatcmpl-refusal",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-08-06",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": null, "refusal": "I can't help with that."},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 6, "total_tokens": 16}
+        })).unwrap();
+
+        match response.as_result() {
+            Err(ApiError::Rejected(reason)) => assert!(reason.contains("I can't help with that.")),
+            other => panic!("Expected Rejected error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_as_result_anthropic_refusal() {
+        let response: ResponseMessage = serde_json::from_value(json!({
+            "id": "msg_refusal",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-haiku-20240307",
+            "content": [],
+            "stop_reason": "refusal",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 10, "output_tokens": 0}
+        })).unwrap();
+
+        assert_eq!(response.normalized_stop_reason(), StopReason::ContentFilter);
+        assert!(matches!(response.as_result(), Err(ApiError::Rejected(_))));
+    }
+
+    #[test]
+    fn test_as_result_content_filter() {
+        let response: ResponseMessage = serde_json::from_value(json!({
+            "id": "chatcmpl-filtered",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": ""},
+                "finish_reason": "content_filter"
+            }],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 0, "total_tokens": 10}
+        })).unwrap();
+
+        assert!(matches!(response.as_result(), Err(ApiError::Rejected(_))));
+    }
+
+    #[test]
+    fn test_as_result_normal_response() {
+        let response: ResponseMessage = serde_json::from_value(json!({
+            "id": "msg_ok",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-haiku-20240307",
+            "content": [{"type": "text", "text": "Hello!"}],
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 10, "output_tokens": 3}
+        })).unwrap();
+
+        let response = response.as_result().unwrap();
+        assert_eq!(response.first_message(), "Hello!");
+    }
+
+    #[test]
+    fn test_tool_inputs_canonical_across_providers() {
+        let openai: ResponseMessage = serde_json::from_value(json!({
+            "id": "chatcmpl-canonical",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {
+                            "name": "book_table",
+                            "arguments": "{ \"party\": {\"size\": 4.0, \"names\": [\"Ann\"]},\n  \"budget\": 1e2, \"tip\": 0.15 }"
+                        }
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 10, "total_tokens": 20}
+        })).unwrap();
+        let anthropic: ResponseMessage = serde_json::from_value(json!({
+            "id": "msg_canonical",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-haiku-20240307",
+            "content": [{
+                "type": "tool_use",
+                "id": "toolu_1",
+                "name": "book_table",
+                "input": {"budget": 100, "party": {"names": ["Ann"], "size": 4.0}, "tip": 0.15}
+            }],
+            "stop_reason": "tool_use",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 10, "output_tokens": 10}
+        })).unwrap();
+
+        let openai_input = &openai.tools().unwrap()[0].input;
+        let anthropic_input = &anthropic.tools().unwrap()[0].input;
+
+        assert_eq!(openai_input, anthropic_input);
+        assert_eq!(openai_input.to_string(), anthropic_input.to_string());
+
+        let blocks = anthropic.content_blocks();
+        let ContentBlock::ToolUse { tool, .. } = &blocks[0] else { panic!("expected a tool use block") };
+        assert_eq!(tool, &anthropic.tools().unwrap()[0]);
+        assert_eq!(&tool.input, openai_input);
+    }
+
+    #[test]
+    fn test_from_json_openai() {
+        let response = ResponseMessage::from_json(json!({
+            "id": "chatcmpl-stored",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hi"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6}
+        })).unwrap();
+
+        assert!(matches!(response, ResponseMessage::OpenAI(_)));
+        assert_eq!(response.first_message(), "Hi");
+    }
+
+    #[test]
+    fn test_from_json_anthropic() {
+        let response = ResponseMessage::from_json(json!({
+            "id": "msg_stored",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-haiku-20240307",
+            "content": [{"type": "text", "text": "Hi"}],
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 5, "output_tokens": 1}
+        })).unwrap();
+
+        assert!(matches!(response, ResponseMessage::Anthropic(_)));
+        assert_eq!(response.first_message(), "Hi");
+    }
+
+    #[test]
+    fn test_from_json_unrecognized() {
+        let result = ResponseMessage::from_json(json!({"id": "x", "model": "y"}));
+
+        assert!(matches!(result, Err(ApiError::ResponseParseError(_))));
+    }
+
+    #[test]
+    fn test_openai_logprobs() {
+        let response: ResponseMessage = serde_json::from_value(json!({
+            "id": "chatcmpl-logprobs",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Yes"},
+                "logprobs": {
+                    "content": [{
+                        "token": "Yes",
+                        "logprob": -0.0019,
+                        "bytes": [89, 101, 115],
+                        "top_logprobs": [
+                            {"token": "Yes", "logprob": -0.0019, "bytes": [89, 101, 115]},
+                            {"token": "No", "logprob": -6.25, "bytes": [78, 111]}
+                        ]
+                    }],
+                    "refusal": null
+                },
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 1, "total_tokens": 11}
+        })).unwrap();
+
+        let logprobs = response.logprobs().expect("Expected logprobs");
+        assert_eq!(logprobs.len(), 1);
+        assert_eq!(logprobs[0].token, "Yes");
+        assert_eq!(logprobs[0].logprob, -0.0019);
+        assert_eq!(logprobs[0].bytes, Some(vec![89, 101, 115]));
+        assert_eq!(logprobs[0].top_logprobs.len(), 2);
+        assert_eq!(logprobs[0].top_logprobs[1].token, "No");
+        assert_eq!(logprobs[0].top_logprobs[1].logprob, -6.25);
+    }
+
+    #[test]
+    fn test_logprobs_absent() {
+        let response: ResponseMessage = serde_json::from_value(json!({
+            "id": "chatcmpl-no-logprobs",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": "Yes"}, "logprobs": null, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 1, "total_tokens": 11}
+        })).unwrap();
+
+        assert!(response.logprobs().is_none());
+    }
+
+    #[test]
+    fn test_choice_logprobs() {
+        let response: ResponseMessage = serde_json::from_value(json!({
+            "id": "chatcmpl-n-logprobs",
+            "object": "chat.completion",
+            "created": 1721962302,
+            "model": "gpt-4o-2024-05-13",
+            "choices": [
+                {"index": 0, "message": {"role": "assistant", "content": "Yes"}, "finish_reason": "stop",
+                 "logprobs": {"content": [{"token": "Yes", "logprob": -0.1, "top_logprobs": []}]}},
+                {"index": 1, "message": {"role": "assistant", "content": "No"}, "finish_reason": "stop",
+                 "logprobs": {"content": [{"token": "No", "logprob": -2.3, "top_logprobs": []}]}}
+            ],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 2, "total_tokens": 12}
+        })).unwrap();
+
+        let choices = response.choices();
+        let second = choices[1].logprobs.as_ref().expect("Expected logprobs on the second choice");
+        assert_eq!(second[0].token, "No");
+        assert_eq!(second[0].logprob, -2.3);
+    }
+
+    fn gemini_response_json() -> serde_json::Value {
+        json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        {"text": "Let me check the weather."},
+                        {"functionCall": {"name": "get_weather", "args": {"location": "Paris", "days": 2.0}}}
+                    ]
+                },
+                "finishReason": "STOP",
+                "index": 0
+            }],
+            "usageMetadata": {"promptTokenCount": 25, "candidatesTokenCount": 12, "totalTokenCount": 37},
+            "modelVersion": "gemini-1.5-flash-002",
+            "responseId": "resp_gemini_1"
+        })
+    }
+
+    #[test]
+    fn test_gemini_response() {
+        let response: ResponseMessage = serde_json::from_value(gemini_response_json()).unwrap();
+
+        assert!(matches!(response, ResponseMessage::Gemini(_)));
+        assert_eq!(response.first_message(), "Let me check the weather.");
+        assert_eq!(response.id(), "resp_gemini_1");
+        assert_eq!(response.model(), "gemini-1.5-flash-002");
+        assert_eq!(response.role(), "model");
+        assert_eq!(response.normalized_role(), Role::Assistant);
+        assert_eq!(response.normalized_stop_reason(), StopReason::EndTurn);
+        assert_eq!(response.usage().input_tokens, 25);
+        assert_eq!(response.usage().output_tokens, 12);
+
+        let tools = response.tools().expect("Expected function calls");
+        assert_eq!(tools, vec![ToolResponse {
+            id: "get_weather".to_string(),
+            name: "get_weather".to_string(),
+            input: json!({"location": "Paris", "days": 2}),
+        }]);
+
+        let blocks = response.content_blocks();
+        assert_eq!(blocks.len(), 2);
+        assert!(matches!(&blocks[0], ContentBlock::Text { index: 0, .. }));
+        assert!(matches!(&blocks[1], ContentBlock::ToolUse { index: 1, .. }));
+    }
+
+    #[test]
+    fn test_gemini_from_json() {
+        let response = ResponseMessage::from_json(gemini_response_json()).unwrap();
+
+        assert!(matches!(response, ResponseMessage::Gemini(_)));
+    }
+
+    #[test]
+    fn test_gemini_blocked_prompt() {
+        let response = ResponseMessage::from_json(json!({
+            "promptFeedback": {"blockReason": "SAFETY"},
+            "usageMetadata": {"promptTokenCount": 8, "totalTokenCount": 8}
+        })).unwrap();
+
+        assert_eq!(response.first_message(), "");
+        assert!(matches!(response.as_result(), Err(ApiError::Rejected(_))));
+    }
+
+    #[test]
+    fn test_bedrock_response() {
+        let response = ResponseMessage::from_json(json!({
+            "output": {
+                "message": {
+                    "role": "assistant",
+                    "content": [
+                        {"text": "Checking the weather."},
+                        {"toolUse": {"toolUseId": "tooluse_1", "name": "get_weather", "input": {"location": "Paris"}}}
+                    ]
+                }
+            },
+            "stopReason": "tool_use",
+            "usage": {"inputTokens": 30, "outputTokens": 15, "totalTokens": 45},
+            "metrics": {"latencyMs": 420}
+        })).unwrap();
+
+        assert!(matches!(response, ResponseMessage::Bedrock(_)));
+        assert_eq!(response.first_message(), "Checking the weather.");
+        assert_eq!(response.role(), "assistant");
+        assert_eq!(response.normalized_stop_reason(), StopReason::ToolUse);
+        assert_eq!(response.usage().input_tokens, 30);
+        assert_eq!(response.usage().output_tokens, 15);
+        assert_eq!(response.tools().unwrap(), vec![ToolResponse {
+            id: "tooluse_1".to_string(),
+            name: "get_weather".to_string(),
+            input: json!({"location": "Paris"}),
+        }]);
+        assert_eq!(response.to_assistant_message().content, json!([
+            {"text": "Checking the weather."},
+            {"toolUse": {"toolUseId": "tooluse_1", "name": "get_weather", "input": {"location": "Paris"}}}
+        ]));
+    }
+
+    #[test]
+    fn test_ollama_response() {
+        let response = ResponseMessage::from_json(json!({
+            "model": "llama3.1",
+            "created_at": "2024-07-25T12:00:00.000000Z",
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [{"function": {"name": "get_weather", "arguments": {"location": "Paris", "days": 2.0}}}]
+            },
+            "done": true,
+            "done_reason": "stop",
+            "total_duration": 4883583458u64,
+            "prompt_eval_count": 26,
+            "eval_count": 12
+        })).unwrap();
+
+        assert!(matches!(response, ResponseMessage::Ollama(_)));
+        assert_eq!(response.model(), "llama3.1");
+        assert_eq!(response.normalized_stop_reason(), StopReason::EndTurn);
+        assert_eq!(response.usage().input_tokens, 26);
+        assert_eq!(response.usage().output_tokens, 12);
+        assert_eq!(response.tools().unwrap(), vec![ToolResponse {
+            id: "get_weather".to_string(),
+            name: "get_weather".to_string(),
+            input: json!({"location": "Paris", "days": 2}),
+        }]);
+        let tool_calls = response.to_assistant_message().tool_calls.unwrap();
+        assert_eq!(tool_calls[0].function.name, "get_weather");
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&tool_calls[0].function.arguments).unwrap(),
+                   json!({"location": "Paris", "days": 2.0}));
+    }
+
+    #[test]
+    fn test_reasoning_content() {
+        let response = ResponseMessage::from_json(json!({
+            "id": "930c60df",
+            "object": "chat.completion",
+            "created": 1737000000,
+            "model": "deepseek-reasoner",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": "9.11 is smaller than 9.8.",
+                    "reasoning_content": "Compare the tenths digit: 1 < 8."
+                },
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 15, "completion_tokens": 40, "total_tokens": 55}
+        })).unwrap();
+
+        assert_eq!(response.first_message(), "9.11 is smaller than 9.8.");
+        assert_eq!(response.reasoning_content(), Some("Compare the tenths digit: 1 < 8."));
+        // The reasoning must not be replayed to the API.
+        assert_eq!(response.to_assistant_message().content, json!("9.11 is smaller than 9.8."));
+    }
+
+    #[test]
+    fn test_perplexity_citations() {
+        let response = ResponseMessage::from_json(json!({
+            "id": "3c90c3cc",
+            "object": "chat.completion",
+            "created": 1724369245,
+            "model": "sonar",
+            "citations": ["https://www.rust-lang.org/", "https://doc.rust-lang.org/book/"],
+            "search_results": [
+                {"title": "Rust Programming Language", "url": "https://www.rust-lang.org/", "date": "2024-08-01"},
+                {"title": "The Rust Programming Language", "url": "https://doc.rust-lang.org/book/"}
+            ],
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Rust is a systems programming language [1][2]."},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 8, "completion_tokens": 12, "total_tokens": 20}
+        })).unwrap();
+
+        assert_eq!(response.citations().unwrap(), ["https://www.rust-lang.org/", "https://doc.rust-lang.org/book/"]);
+        let search_results = response.search_results().unwrap();
+        assert_eq!(search_results[0].title, "Rust Programming Language");
+        assert_eq!(search_results[0].date.as_deref(), Some("2024-08-01"));
+        assert_eq!(search_results[1].date, None);
+    }
+
+    #[test]
+    fn test_tool_call_arguments_as_object() {
+        let response = ResponseMessage::from_json(json!({
+            "id": "",
+            "object": "chat.completion",
+            "created": 1724369245,
+            "model": "meta-llama/Meta-Llama-3.1-8B-Instruct",
+            "system_fingerprint": "2.2.0-sha-db7e043",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "0",
+                        "type": "function",
+                        "function": {"name": "get_weather", "arguments": {"location": "Paris"}}
+                    }]
+                },
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 30, "completion_tokens": 10, "total_tokens": 40}
+        })).unwrap();
+
+        assert_eq!(response.tools().unwrap()[0].input, json!({"location": "Paris"}));
+    }
+}
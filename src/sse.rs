@@ -0,0 +1,119 @@
+//! Server-sent event passthrough for `axum` handlers.
+//!
+//! Enabled with the `axum` feature. `into_sse` turns a stream of `StreamEvent`s into an `axum`
+//! SSE response, so a chat backend can proxy a model's output to a browser without mapping
+//! events itself. Each event is named after its type and carries the event as JSON, e.g.
+//!
+//! ```text
+//! event: text_delta
+//! data: {"index":0,"text":"Hello","type":"text_delta"}
+//! ```
+//!
+//! An error ends the response with an `error` event whose data is `{"message": "..."}`.
+//!
+//! `axum` handlers need a `'static` stream, so the usual source is
+//! `RequestBuilder::stream_to_channel`:
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use axum::extract::State;
+//! # use axum::response::IntoResponse;
+//! # use llm_bridge::client::LlmClient;
+//! async fn chat(State(client): State<Arc<LlmClient>>, prompt: String) -> impl IntoResponse {
+//!     match client.request().user_message(&prompt).stream_to_channel(32).await {
+//!         Ok((receiver, _handle)) => llm_bridge::sse::receiver_into_sse(receiver).into_response(),
+//!         Err(e) => (axum::http::StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
+//!     }
+//! }
+//! ```
+
+use std::convert::Infallible;
+use axum::response::sse::{Event, Sse};
+use futures_util::{Stream, StreamExt};
+use serde_json::json;
+use tokio::sync::mpsc;
+use crate::error::ApiError;
+use crate::streaming::StreamEvent;
+
+/// Converts a `StreamEvent` into an SSE event named after its type, with the event as JSON data.
+pub fn sse_event(event: &StreamEvent) -> Event {
+    let data = serde_json::to_value(event).unwrap_or_default();
+    let name = data["type"].as_str().unwrap_or("message").to_string();
+    Event::default().event(name).data(data.to_string())
+}
+
+/// Returns an SSE response forwarding the events of `stream`, ending with an `error` event if
+/// the stream fails.
+pub fn into_sse<S>(stream: S) -> Sse<impl Stream<Item = Result<Event, Infallible>>>
+where
+    S: Stream<Item = Result<StreamEvent, ApiError>> + Send + 'static,
+{
+    let events = stream
+        .scan(false, |failed, event| {
+            if *failed {
+                return futures_util::future::ready(None);
+            }
+            let event = match event {
+                Ok(event) => sse_event(&event),
+                Err(error) => {
+                    *failed = true;
+                    Event::default().event("error").data(json!({"message": error.to_string()}).to_string())
+                }
+            };
+            futures_util::future::ready(Some(Ok(event)))
+        });
+    Sse::new(events)
+}
+
+/// Returns an SSE response forwarding the events received from `receiver`, such as the one
+/// returned by `RequestBuilder::stream_to_channel`, ending with an `error` event if an error
+/// is received.
+pub fn receiver_into_sse(
+    receiver: mpsc::Receiver<Result<StreamEvent, ApiError>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    into_sse(futures_util::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|event| (event, receiver))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+
+    async fn body_text(sse: impl IntoResponse) -> String {
+        let body = axum::body::to_bytes(sse.into_response().into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_into_sse() {
+        let events = futures_util::stream::iter(vec![
+            Ok(StreamEvent::TextDelta { index: 0, text: "Hi".to_string() }),
+            Err(ApiError::ServerError("overloaded".to_string())),
+            Ok(StreamEvent::MessageStop { stop_reason: None, stop_sequence: None }),
+        ]);
+
+        let body = body_text(into_sse(events)).await;
+
+        assert_eq!(body, concat!(
+            "event: text_delta\ndata: {\"index\":0,\"text\":\"Hi\",\"type\":\"text_delta\"}\n\n",
+            "event: error\ndata: {\"message\":\"Server error returned from API: overloaded\"}\n\n",
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_receiver_into_sse() {
+        let (sender, receiver) = mpsc::channel(4);
+        sender.send(Ok(StreamEvent::TextDelta { index: 0, text: "Hi".to_string() })).await.unwrap();
+        sender.send(Err(ApiError::Cancelled)).await.unwrap();
+        drop(sender);
+
+        let body = body_text(receiver_into_sse(receiver)).await;
+
+        assert_eq!(body, concat!(
+            "event: text_delta\ndata: {\"index\":0,\"text\":\"Hi\",\"type\":\"text_delta\"}\n\n",
+            "event: error\ndata: {\"message\":\"Request was cancelled\"}\n\n",
+        ));
+    }
+}
@@ -0,0 +1,1167 @@
+//! Streaming responses.
+//!
+//! `RequestBuilder::stream` sends a request with `stream: true` and returns an [`EventStream`]
+//! of [`StreamEvent`]s, decoded from Anthropic server-sent events or OpenAI chat completion
+//! chunks, so callers can render output as it is generated with the same code for every
+//! provider.
+//!
+//! Consumers who need the provider's own events can split a response body with [`SseDecoder`]
+//! and deserialize each event's data into the typed models: [`AnthropicStreamEvent`], or
+//! [`OpenAIStreamChunk::parse`] for OpenAI-compatible providers.
+//!
+//! ```no_run
+//! # use futures_util::StreamExt;
+//! # use llm_bridge::client::{ClientLlm, LlmClient};
+//! # use llm_bridge::streaming::StreamEvent;
+//! # async fn example() -> Result<(), llm_bridge::error::ApiError> {
+//! let client = LlmClient::new(ClientLlm::Anthropic, "api-key".to_string());
+//! let mut stream = client.request().user_message("Tell me a story").stream();
+//! while let Some(event) = stream.next().await {
+//!     if let StreamEvent::TextDelta { text, .. } = event? {
+//!         print!("{}", text);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::pin::Pin;
+use std::time::Duration;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::client::ClientLlm;
+use crate::error::ApiError;
+use crate::text::SentenceBuffer;
+use crate::response::{
+    AnthropicContentBlock, AnthropicResponse, AnthropicUsage, CommonUsage, OpenAIChoice, OpenAIFunction,
+    OpenAIMessage, OpenAIResponse, OpenAIToolCall, OpenAIUsage, ResponseMessage, ToolResponse,
+};
+
+/// A stream of events from a streaming response.
+pub type EventStream<'a> = Pin<Box<dyn Stream<Item = Result<StreamEvent, ApiError>> + Send + 'a>>;
+
+/// A stream of complete sentences from a streaming response. See `sentence_stream`.
+pub type SentenceStream<'a> = Pin<Box<dyn Stream<Item = Result<String, ApiError>> + Send + 'a>>;
+
+/// An incremental update from a streaming response, normalized across providers.
+///
+/// `index` is the content block index for Anthropic, and the choice index (for text) or tool
+/// call index (for tool calls) for OpenAI.
+///
+/// Serializes with a `type` tag in snake case, e.g. `{"type":"text_delta","index":0,"text":"Hi"}`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    /// The first event of a stream, with the id of the response and the model generating it.
+    MessageStart { id: String, model: String },
+    /// A fragment of generated text.
+    TextDelta { index: usize, text: String },
+    /// A fragment of a tool call. The first fragment of each call carries its `id` and `name`;
+    /// the `arguments` fragments concatenate to the JSON-encoded tool input.
+    ToolCallDelta {
+        index: usize,
+        id: Option<String>,
+        name: Option<String>,
+        arguments: String,
+    },
+    /// A tool call whose arguments have been fully received, emitted after the `ToolCallDelta`
+    /// that completed them, or before `MessageStop` for calls without arguments.
+    ToolCallComplete(ToolResponse),
+    /// A tool call whose arguments were still not valid JSON when the message ended, e.g.
+    /// because generation stopped at `max_tokens`. `arguments` holds the fragments received.
+    ToolCallIncomplete {
+        index: usize,
+        id: String,
+        name: String,
+        arguments: String,
+    },
+    /// Token usage for the whole response, sent once near the end of the stream. OpenAI and
+    /// xAI streams request it with `stream_options.include_usage`.
+    Usage(CommonUsage),
+    /// Generation finished, with the provider's stop reason if it reported one, and the stop
+    /// sequence that ended it (Anthropic only).
+    MessageStop {
+        stop_reason: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stop_sequence: Option<String>,
+    },
+    /// The connection dropped and, under `StreamRecovery`, the request was sent again from
+    /// scratch. Content received before this event should be discarded.
+    Restarted,
+}
+
+/// What `RequestBuilder::stream` does when a stream fails part way through: the connection
+/// drops, no data arrives within the idle timeout, the provider reports an error mid-stream,
+/// or the body ends before the provider signals completion.
+///
+/// Errors sending the request are retried by the client's own retry settings instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum StreamRecovery {
+    /// Return the error. The default.
+    #[default]
+    Fail,
+    /// Send the request again from scratch, emitting `StreamEvent::Restarted` if content had
+    /// already been received.
+    Restart { max_retries: u32 },
+    /// Continue from the text received so far where the provider supports it, by sending it
+    /// back as a partial assistant turn, so the stream carries on where it stopped. Anthropic
+    /// responses containing only text are continued; anything else is restarted.
+    Resume { max_retries: u32 },
+}
+
+impl StreamRecovery {
+    pub(crate) fn max_retries(&self) -> u32 {
+        match self {
+            StreamRecovery::Fail => 0,
+            StreamRecovery::Restart { max_retries } | StreamRecovery::Resume { max_retries } => *max_retries,
+        }
+    }
+}
+
+/// Returns `true` for errors that end a stream part way through and may succeed on retry.
+pub(crate) fn is_recoverable(error: &ApiError) -> bool {
+    matches!(error, ApiError::RequestError(_) | ApiError::StreamIdleTimeout(_) | ApiError::ServerError(_))
+}
+
+/// Assembles `ToolCallDelta` fragments into complete `ToolResponse`s.
+///
+/// `RequestBuilder::stream` already emits `StreamEvent::ToolCallComplete` using this; it is
+/// public for consumers that process deltas themselves.
+#[derive(Debug, Default)]
+pub struct ToolCallAccumulator {
+    calls: BTreeMap<usize, PartialToolCall>,
+}
+
+#[derive(Debug, Default)]
+struct PartialToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+impl ToolCallAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a fragment, returning the tool call once its arguments parse as JSON. Events other
+    /// than `ToolCallDelta` are ignored.
+    pub fn push(&mut self, event: &StreamEvent) -> Option<ToolResponse> {
+        let StreamEvent::ToolCallDelta { index, id, name, arguments } = event else {
+            return None;
+        };
+        let call = self.calls.entry(*index).or_default();
+        if let Some(id) = id {
+            call.id.clone_from(id);
+        }
+        if let Some(name) = name {
+            call.name.clone_from(name);
+        }
+        call.arguments.push_str(arguments);
+        let input = serde_json::from_str(&call.arguments).ok()?;
+        let call = self.calls.remove(index)?;
+        Some(ToolResponse { id: call.id, name: call.name, input })
+    }
+
+    /// Returns the calls still pending once the message has ended, as `ToolCallComplete`
+    /// events, or `ToolCallIncomplete` for calls whose arguments don't parse. Calls that never
+    /// received arguments get an empty object as input.
+    pub fn finish_events(&mut self) -> Vec<StreamEvent> {
+        std::mem::take(&mut self.calls).into_iter()
+            .map(|(index, call)| {
+                let input = if call.arguments.trim().is_empty() {
+                    Ok(Value::Object(Default::default()))
+                } else {
+                    serde_json::from_str(&call.arguments)
+                };
+                match input {
+                    Ok(input) => StreamEvent::ToolCallComplete(ToolResponse { id: call.id, name: call.name, input }),
+                    Err(_) => StreamEvent::ToolCallIncomplete {
+                        index,
+                        id: call.id,
+                        name: call.name,
+                        arguments: call.arguments,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the calls still pending once the message has ended. Calls that never received
+    /// arguments get an empty object as input; calls with arguments that don't parse are
+    /// returned as `ApiError::ResponseParseError`.
+    pub fn finish(&mut self) -> Result<Vec<ToolResponse>, ApiError> {
+        std::mem::take(&mut self.calls).into_values()
+            .map(|call| {
+                let input = if call.arguments.trim().is_empty() {
+                    Value::Object(Default::default())
+                } else {
+                    serde_json::from_str(&call.arguments)?
+                };
+                Ok(ToolResponse { id: call.id, name: call.name, input })
+            })
+            .collect()
+    }
+}
+
+/// An event from the Anthropic Messages API stream.
+///
+/// Event types this crate doesn't know about deserialize to `Unknown`, as the API may add new
+/// ones.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicStreamEvent {
+    /// Starts the message, with its metadata and the input token count.
+    MessageStart { message: AnthropicStreamMessage },
+    /// Starts the content block at `index`.
+    ContentBlockStart { index: usize, content_block: AnthropicStreamContentBlock },
+    /// Appends to the content block at `index`.
+    ContentBlockDelta { index: usize, delta: AnthropicContentDelta },
+    /// Ends the content block at `index`.
+    ContentBlockStop { index: usize },
+    /// Reports the stop reason and the cumulative output token count.
+    MessageDelta {
+        delta: AnthropicMessageDelta,
+        #[serde(default)]
+        usage: AnthropicDeltaUsage,
+    },
+    /// Ends the message.
+    MessageStop,
+    /// Keeps the connection alive.
+    Ping,
+    /// An error that occurred after the stream started, such as `overloaded_error`.
+    Error { error: AnthropicStreamError },
+    #[serde(other)]
+    Unknown,
+}
+
+/// The message metadata sent in `message_start`. Its content is always empty; it arrives in
+/// the content block events that follow.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AnthropicStreamMessage {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub role: String,
+    #[serde(default)]
+    pub usage: AnthropicStreamUsage,
+}
+
+/// Token usage reported in `message_start`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AnthropicStreamUsage {
+    #[serde(default)]
+    pub input_tokens: usize,
+    #[serde(default)]
+    pub output_tokens: usize,
+}
+
+/// The initial state of a content block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicStreamContentBlock {
+    Text { text: String },
+    /// A tool call; its input arrives as `InputJsonDelta`s.
+    ToolUse { id: String, name: String, #[serde(default)] input: Value },
+    #[serde(other)]
+    Unknown,
+}
+
+/// An incremental update to a content block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicContentDelta {
+    TextDelta { text: String },
+    /// A fragment of the JSON-encoded tool input.
+    InputJsonDelta { partial_json: String },
+    #[serde(other)]
+    Unknown,
+}
+
+/// The top-level message changes sent in `message_delta`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AnthropicMessageDelta {
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub stop_sequence: Option<String>,
+}
+
+/// Token usage reported in `message_delta`. The counts are cumulative for the message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AnthropicDeltaUsage {
+    /// Only sent by newer API versions; otherwise the count from `message_start` applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<usize>,
+    #[serde(default)]
+    pub output_tokens: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AnthropicStreamError {
+    #[serde(rename = "type")]
+    pub error_type: String,
+    pub message: String,
+}
+
+/// A chunk of an OpenAI chat completion stream.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct OpenAIStreamChunk {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub object: String,
+    #[serde(default)]
+    pub created: i64,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub choices: Vec<OpenAIStreamChoice>,
+    /// Identifies the backend configuration, as on non-streaming responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+    /// Only present on the final chunk, when usage was requested with
+    /// `stream_options.include_usage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<OpenAIUsage>,
+    /// Set instead of `choices` when the server fails mid-stream.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Value>,
+}
+
+impl OpenAIStreamChunk {
+    /// Parses the data of a server-sent event, returning `None` for the `[DONE]` sentinel that
+    /// ends the stream.
+    pub fn parse(data: &str) -> Result<Option<Self>, ApiError> {
+        if data == "[DONE]" {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(data)?))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct OpenAIStreamChoice {
+    #[serde(default)]
+    pub index: usize,
+    #[serde(default)]
+    pub delta: DeltaMessage,
+    /// Set on the last chunk for this choice.
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// The part of the assistant message added by a chunk.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct DeltaMessage {
+    /// Only set on the first chunk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Reasoning text from DeepSeek's reasoning models.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<DeltaToolCall>>,
+}
+
+/// A fragment of a tool call. The first fragment carries the `id` and function name; later
+/// fragments with the same `index` append to the arguments.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct DeltaToolCall {
+    #[serde(default)]
+    pub index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub call_type: Option<String>,
+    #[serde(default)]
+    pub function: DeltaFunction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct DeltaFunction {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
+}
+
+/// A single server-sent event.
+#[derive(Debug, Default, PartialEq)]
+pub struct SseEvent {
+    /// The `event:` field, if the server sent one.
+    pub event: Option<String>,
+    /// The `data:` lines, joined with newlines.
+    pub data: String,
+}
+
+/// Splits a byte stream into server-sent events. Chunks may end anywhere, including inside a
+/// line or a multi-byte character.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Option<String>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `bytes`, returning the events they completed.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            events.extend(self.process_line(line.trim_end_matches(['\n', '\r'])));
+        }
+        events
+    }
+
+    /// Returns the final event once the byte stream has ended, if it wasn't terminated by a
+    /// blank line.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).into_owned();
+        if let Some(event) = self.process_line(line.trim_end_matches('\r')) {
+            return Some(event);
+        }
+        self.process_line("")
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            let data = self.data.take();
+            let event = self.event.take();
+            return data.map(|data| SseEvent { event, data });
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => match &mut self.data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => self.data = Some(value.to_string()),
+            },
+            _ => {}
+        }
+        None
+    }
+}
+
+/// The streaming wire format of a provider.
+#[derive(Debug, Clone, Copy)]
+enum StreamFormat {
+    Anthropic,
+    OpenAI,
+}
+
+impl StreamFormat {
+    fn for_client(client_type: &ClientLlm) -> Result<Self, ApiError> {
+        match client_type {
+            ClientLlm::Anthropic => Ok(StreamFormat::Anthropic),
+            ClientLlm::OpenAI | ClientLlm::Mistral | ClientLlm::XAI => Ok(StreamFormat::OpenAI),
+            other => Err(ApiError::InvalidUsage(format!("Streaming is not supported by the {:?} API", other))),
+        }
+    }
+}
+
+/// Rebuilds the `ResponseMessage` the non-streaming API would have returned from the events
+/// of a stream, so callers can render a stream as it arrives and keep their existing
+/// post-processing.
+///
+/// Responses are rebuilt from what the events carry: OpenAI `created` timestamps and log
+/// probabilities are not reported, and OpenAI tool calls are attributed to the first choice.
+#[derive(Debug)]
+pub struct ResponseCollector {
+    format: StreamFormat,
+    id: String,
+    model: String,
+    text: BTreeMap<usize, String>,
+    tool_calls: BTreeMap<usize, ToolResponse>,
+    tool_call_indices: HashMap<String, usize>,
+    stop_reason: Option<String>,
+    stop_sequence: Option<String>,
+    usage: CommonUsage,
+}
+
+impl ResponseCollector {
+    /// Creates a collector for a stream from `client_type`, or returns
+    /// `ApiError::InvalidUsage` if streaming isn't supported for it.
+    pub fn new(client_type: &ClientLlm) -> Result<Self, ApiError> {
+        Ok(ResponseCollector {
+            format: StreamFormat::for_client(client_type)?,
+            id: String::new(),
+            model: String::new(),
+            text: BTreeMap::new(),
+            tool_calls: BTreeMap::new(),
+            tool_call_indices: HashMap::new(),
+            stop_reason: None,
+            stop_sequence: None,
+            usage: CommonUsage::default(),
+        })
+    }
+
+    /// Records an event.
+    pub fn push(&mut self, event: &StreamEvent) {
+        match event {
+            StreamEvent::MessageStart { id, model } => {
+                self.id.clone_from(id);
+                self.model.clone_from(model);
+            }
+            StreamEvent::TextDelta { index, text } => self.text.entry(*index).or_default().push_str(text),
+            StreamEvent::ToolCallDelta { index, id: Some(id), .. } => {
+                self.tool_call_indices.insert(id.clone(), *index);
+            }
+            StreamEvent::ToolCallDelta { .. } => {}
+            // A truncated call can't be answered; `stop_reason` reports why it was cut off.
+            StreamEvent::ToolCallIncomplete { .. } => {}
+            StreamEvent::ToolCallComplete(tool_call) => {
+                let index = self.tool_call_indices.get(&tool_call.id).copied()
+                    .unwrap_or_else(|| self.tool_calls.keys().next_back().map_or(0, |last| last + 1));
+                self.tool_calls.insert(index, tool_call.clone());
+            }
+            StreamEvent::Usage(usage) => self.usage = usage.clone(),
+            StreamEvent::MessageStop { stop_reason, stop_sequence } => {
+                self.stop_reason.clone_from(stop_reason);
+                self.stop_sequence.clone_from(stop_sequence);
+            }
+            StreamEvent::Restarted => {
+                self.text.clear();
+                self.tool_calls.clear();
+                self.tool_call_indices.clear();
+                self.stop_reason = None;
+                self.stop_sequence = None;
+                self.usage = CommonUsage::default();
+            }
+        }
+    }
+
+    /// Returns the response assembled from the recorded events.
+    pub fn finish(self) -> ResponseMessage {
+        match self.format {
+            StreamFormat::Anthropic => self.finish_anthropic(),
+            StreamFormat::OpenAI => self.finish_openai(),
+        }
+    }
+
+    fn finish_anthropic(self) -> ResponseMessage {
+        let mut blocks: BTreeMap<usize, AnthropicContentBlock> = self.text.into_iter()
+            .map(|(index, text)| (index, AnthropicContentBlock::Text { text, block_type: "text".to_string() }))
+            .collect();
+        blocks.extend(self.tool_calls.into_iter().map(|(index, tool_call)| (index, AnthropicContentBlock::ToolUse {
+            block_type: "tool_use".to_string(),
+            id: tool_call.id,
+            name: tool_call.name,
+            input: tool_call.input,
+        })));
+        ResponseMessage::Anthropic(AnthropicResponse {
+            id: self.id,
+            role: "assistant".to_string(),
+            content: blocks.into_values().collect(),
+            model: self.model,
+            stop_reason: self.stop_reason.unwrap_or_default(),
+            stop_sequence: self.stop_sequence,
+            usage: AnthropicUsage {
+                input_tokens: self.usage.input_tokens,
+                output_tokens: self.usage.output_tokens,
+                cache_read_input_tokens: self.usage.cache_read_input_tokens,
+                cache_creation_input_tokens: self.usage.cache_creation_input_tokens,
+            },
+            extra: Default::default(),
+            raw_body: None,
+            meta: None,
+        })
+    }
+
+    fn finish_openai(mut self) -> ResponseMessage {
+        let tool_calls: Vec<OpenAIToolCall> = std::mem::take(&mut self.tool_calls).into_values()
+            .map(|tool_call| OpenAIToolCall {
+                id: tool_call.id,
+                call_type: "function".to_string(),
+                function: OpenAIFunction { name: tool_call.name, arguments: tool_call.input.to_string() },
+            })
+            .collect();
+        if !tool_calls.is_empty() {
+            self.text.entry(0).or_default();
+        }
+        let mut tool_calls = Some(tool_calls).filter(|tool_calls| !tool_calls.is_empty());
+        let choices = self.text.into_iter()
+            .map(|(index, text)| OpenAIChoice {
+                index,
+                message: OpenAIMessage {
+                    role: "assistant".to_string(),
+                    content: Some(text).filter(|text| !text.is_empty()),
+                    tool_calls: if index == 0 { tool_calls.take() } else { None },
+                    refusal: None,
+                    reasoning_content: None,
+                },
+                logprobs: None,
+                finish_reason: self.stop_reason.clone(),
+            })
+            .collect();
+        ResponseMessage::OpenAI(OpenAIResponse {
+            id: self.id,
+            object: "chat.completion".to_string(),
+            created: 0,
+            model: self.model,
+            choices,
+            usage: OpenAIUsage {
+                prompt_tokens: self.usage.input_tokens,
+                completion_tokens: self.usage.output_tokens,
+                total_tokens: self.usage.input_tokens + self.usage.output_tokens,
+                ..Default::default()
+            },
+            system_fingerprint: None,
+            citations: None,
+            search_results: None,
+            extra: Default::default(),
+            raw_body: None,
+            meta: None,
+        })
+    }
+}
+
+/// Consumes `stream` and returns the `ResponseMessage` the non-streaming API would have
+/// returned. See `ResponseCollector`.
+pub async fn collect_stream(mut stream: EventStream<'_>, client_type: &ClientLlm) -> Result<ResponseMessage, ApiError> {
+    let mut collector = ResponseCollector::new(client_type)?;
+    while let Some(event) = stream.next().await {
+        collector.push(&event?);
+    }
+    Ok(collector.finish())
+}
+
+/// Buffers the text of `events` with a `SentenceBuffer` and yields it one complete sentence at a
+/// time, e.g. for text-to-speech. The remaining text is flushed when the message stops or the
+/// stream ends, and discarded on `StreamEvent::Restarted`.
+pub fn sentence_stream(mut events: EventStream<'_>) -> SentenceStream<'_> {
+    Box::pin(async_stream::try_stream! {
+        let mut buffer = SentenceBuffer::new();
+        while let Some(event) = events.next().await {
+            match event? {
+                StreamEvent::TextDelta { text, .. } => {
+                    for sentence in buffer.push(&text) {
+                        yield sentence;
+                    }
+                }
+                StreamEvent::MessageStop { .. } => {
+                    if let Some(remainder) = buffer.finish() {
+                        yield remainder;
+                    }
+                }
+                StreamEvent::Restarted => buffer = SentenceBuffer::new(),
+                _ => {}
+            }
+        }
+        if let Some(remainder) = buffer.finish() {
+            yield remainder;
+        }
+    })
+}
+
+/// Converts a provider's server-sent events into `StreamEvent`s.
+pub(crate) struct EventDecoder {
+    format: StreamFormat,
+    started: bool,
+    input_tokens: usize,
+    stop_reason: Option<String>,
+    stop_sequence: Option<String>,
+    done: bool,
+    tool_calls: ToolCallAccumulator,
+}
+
+impl EventDecoder {
+    /// Creates a decoder for the streaming format of `client_type`, or returns
+    /// `ApiError::InvalidUsage` if streaming isn't supported for it.
+    pub fn new(client_type: &ClientLlm) -> Result<Self, ApiError> {
+        Ok(EventDecoder {
+            format: StreamFormat::for_client(client_type)?,
+            started: false,
+            input_tokens: 0,
+            stop_reason: None,
+            stop_sequence: None,
+            done: false,
+            tool_calls: ToolCallAccumulator::new(),
+        })
+    }
+
+    /// Returns `true` once the provider has signalled the end of the stream.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    pub fn decode(&mut self, sse: &SseEvent) -> Result<Vec<StreamEvent>, ApiError> {
+        let events = match self.format {
+            StreamFormat::Anthropic => self.decode_anthropic(sse)?,
+            StreamFormat::OpenAI => self.decode_openai(sse)?,
+        };
+        let mut output = Vec::with_capacity(events.len());
+        for event in events {
+            if matches!(event, StreamEvent::MessageStop { .. }) {
+                output.extend(self.tool_calls.finish_events());
+            }
+            let complete = self.tool_calls.push(&event);
+            output.push(event);
+            output.extend(complete.map(StreamEvent::ToolCallComplete));
+        }
+        Ok(output)
+    }
+
+    fn decode_anthropic(&mut self, sse: &SseEvent) -> Result<Vec<StreamEvent>, ApiError> {
+        let events = match serde_json::from_str(&sse.data)? {
+            AnthropicStreamEvent::MessageStart { message } => {
+                self.input_tokens = message.usage.input_tokens;
+                vec![StreamEvent::MessageStart { id: message.id, model: message.model }]
+            }
+            AnthropicStreamEvent::ContentBlockStart { index, content_block } => match content_block {
+                AnthropicStreamContentBlock::ToolUse { id, name, .. } => vec![StreamEvent::ToolCallDelta {
+                    index,
+                    id: Some(id),
+                    name: Some(name),
+                    arguments: String::new(),
+                }],
+                AnthropicStreamContentBlock::Text { text } => text_delta(index, text),
+                AnthropicStreamContentBlock::Unknown => vec![],
+            },
+            AnthropicStreamEvent::ContentBlockDelta { index, delta } => match delta {
+                AnthropicContentDelta::TextDelta { text } => text_delta(index, text),
+                AnthropicContentDelta::InputJsonDelta { partial_json } => vec![StreamEvent::ToolCallDelta {
+                    index,
+                    id: None,
+                    name: None,
+                    arguments: partial_json,
+                }],
+                AnthropicContentDelta::Unknown => vec![],
+            },
+            AnthropicStreamEvent::MessageDelta { delta, usage } => {
+                self.stop_reason = delta.stop_reason;
+                self.stop_sequence = delta.stop_sequence;
+                vec![StreamEvent::Usage(CommonUsage {
+                    input_tokens: usage.input_tokens.unwrap_or(self.input_tokens),
+                    output_tokens: usage.output_tokens,
+                    ..Default::default()
+                })]
+            }
+            AnthropicStreamEvent::MessageStop => {
+                self.done = true;
+                vec![StreamEvent::MessageStop {
+                    stop_reason: self.stop_reason.take(),
+                    stop_sequence: self.stop_sequence.take(),
+                }]
+            }
+            AnthropicStreamEvent::Error { error } => return Err(ApiError::ServerError(
+                format!("Stream error: {} - {}", error.error_type, error.message))),
+            AnthropicStreamEvent::ContentBlockStop { .. }
+            | AnthropicStreamEvent::Ping
+            | AnthropicStreamEvent::Unknown => vec![],
+        };
+        Ok(events)
+    }
+
+    fn decode_openai(&mut self, sse: &SseEvent) -> Result<Vec<StreamEvent>, ApiError> {
+        let Some(chunk) = OpenAIStreamChunk::parse(&sse.data)? else {
+            self.done = true;
+            return Ok(vec![StreamEvent::MessageStop { stop_reason: self.stop_reason.take(), stop_sequence: None }]);
+        };
+        if let Some(error) = chunk.error {
+            return Err(ApiError::ServerError(format!("Stream error: {}", error)));
+        }
+        let mut events = Vec::new();
+        if !self.started {
+            self.started = true;
+            events.push(StreamEvent::MessageStart { id: chunk.id, model: chunk.model });
+        }
+        for choice in chunk.choices {
+            events.extend(text_delta(choice.index, choice.delta.content.unwrap_or_default()));
+            for tool_call in choice.delta.tool_calls.into_iter().flatten() {
+                events.push(StreamEvent::ToolCallDelta {
+                    index: tool_call.index,
+                    id: tool_call.id,
+                    name: tool_call.function.name,
+                    arguments: tool_call.function.arguments.unwrap_or_default(),
+                });
+            }
+            if let Some(finish_reason) = choice.finish_reason {
+                self.stop_reason.get_or_insert(finish_reason);
+            }
+        }
+        if let Some(usage) = chunk.usage {
+            events.push(StreamEvent::Usage(CommonUsage::from(&usage)));
+        }
+        Ok(events)
+    }
+}
+
+/// Decodes the body of a successful streaming response into events.
+///
+/// Fails with `ApiError::StreamIdleTimeout` if no data arrives for `idle_timeout`.
+pub(crate) fn decode_event_stream(
+    response: reqwest::Response,
+    mut decoder: EventDecoder,
+    idle_timeout: Option<Duration>,
+) -> EventStream<'static> {
+    Box::pin(async_stream::try_stream! {
+        let mut sse = SseDecoder::new();
+        let mut body = response.bytes_stream();
+        loop {
+            let chunk = match idle_timeout {
+                Some(timeout) => tokio::time::timeout(timeout, body.next()).await
+                    .map_err(|_| ApiError::StreamIdleTimeout(timeout))?,
+                None => body.next().await,
+            };
+            let Some(chunk) = chunk else { break };
+            for event in sse.push(&chunk?) {
+                for stream_event in decoder.decode(&event)? {
+                    yield stream_event;
+                }
+            }
+            if decoder.is_done() {
+                break;
+            }
+        }
+        if !decoder.is_done() {
+            if let Some(event) = sse.finish() {
+                for stream_event in decoder.decode(&event)? {
+                    yield stream_event;
+                }
+            }
+        }
+    })
+}
+
+/// Returns a `TextDelta` for `text`, or nothing if it is empty.
+fn text_delta(index: usize, text: String) -> Vec<StreamEvent> {
+    if text.is_empty() { vec![] } else { vec![StreamEvent::TextDelta { index, text }] }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_all(client_type: ClientLlm, body: &str) -> Vec<StreamEvent> {
+        let mut sse = SseDecoder::default();
+        let mut decoder = EventDecoder::new(&client_type).unwrap();
+        // Feed the body in small pieces to exercise events split across chunks.
+        let mut events = Vec::new();
+        for chunk in body.as_bytes().chunks(7) {
+            for event in sse.push(chunk) {
+                events.extend(decoder.decode(&event).unwrap());
+            }
+        }
+        assert!(decoder.is_done());
+        events
+    }
+
+    #[test]
+    fn test_sse_decoder() {
+        let mut decoder = SseDecoder::default();
+
+        let mut events = decoder.push(b": keep-alive\r\nevent: ping\r\ndata: {}\r\n\r\ndata: line one\ndata: li");
+        events.extend(decoder.push("ne two — ok\n\ndata: trailing".as_bytes()));
+        events.extend(decoder.finish());
+
+        assert_eq!(events, vec![
+            SseEvent { event: Some("ping".to_string()), data: "{}".to_string() },
+            SseEvent { event: None, data: "line one\nline two — ok".to_string() },
+            SseEvent { event: None, data: "trailing".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_decode_anthropic_stream() {
+        let body = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"role\":\"assistant\",\"content\":[],\"usage\":{\"input_tokens\":25,\"output_tokens\":1}}}\n\n",
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+            "event: ping\n",
+            "data: {\"type\":\"ping\"}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Let me check.\"}}\n\n",
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_1\",\"name\":\"get_weather\",\"input\":{}}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"location\\\":\"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\" \\\"Paris\\\"}\"}}\n\n",
+            "event: message_delta\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\",\"stop_sequence\":null},\"usage\":{\"output_tokens\":42}}\n\n",
+            "event: message_stop\n",
+            "data: {\"type\":\"message_stop\"}\n\n",
+        );
+
+        assert_eq!(decode_all(ClientLlm::Anthropic, body), vec![
+            StreamEvent::MessageStart { id: "msg_1".to_string(), model: String::new() },
+            StreamEvent::TextDelta { index: 0, text: "Let me check.".to_string() },
+            StreamEvent::ToolCallDelta { index: 1, id: Some("toolu_1".to_string()), name: Some("get_weather".to_string()), arguments: String::new() },
+            StreamEvent::ToolCallDelta { index: 1, id: None, name: None, arguments: "{\"location\":".to_string() },
+            StreamEvent::ToolCallDelta { index: 1, id: None, name: None, arguments: " \"Paris\"}".to_string() },
+            StreamEvent::ToolCallComplete(ToolResponse {
+                id: "toolu_1".to_string(),
+                name: "get_weather".to_string(),
+                input: serde_json::json!({"location": "Paris"}),
+            }),
+            StreamEvent::Usage(CommonUsage { input_tokens: 25, output_tokens: 42, ..Default::default() }),
+            StreamEvent::MessageStop { stop_reason: Some("tool_use".to_string()), stop_sequence: None },
+        ]);
+    }
+
+    #[test]
+    fn test_anthropic_stream_event_types() {
+        let mut decoder = SseDecoder::new();
+        let events: Vec<AnthropicStreamEvent> = decoder.push(concat!(
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_1\",\"name\":\"get_weather\",\"input\":{}}}\n\n",
+            "event: content_block_stop\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":1}\n\n",
+            "event: error\n",
+            "data: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n",
+            "event: future_event\n",
+            "data: {\"type\":\"future_event\",\"detail\":1}\n\n",
+        ).as_bytes())
+            .iter()
+            .map(|event| serde_json::from_str(&event.data).unwrap())
+            .collect();
+
+        assert_eq!(events, vec![
+            AnthropicStreamEvent::ContentBlockStart {
+                index: 1,
+                content_block: AnthropicStreamContentBlock::ToolUse {
+                    id: "toolu_1".to_string(),
+                    name: "get_weather".to_string(),
+                    input: serde_json::json!({}),
+                },
+            },
+            AnthropicStreamEvent::ContentBlockStop { index: 1 },
+            AnthropicStreamEvent::Error {
+                error: AnthropicStreamError { error_type: "overloaded_error".to_string(), message: "Overloaded".to_string() },
+            },
+            AnthropicStreamEvent::Unknown,
+        ]);
+    }
+
+    #[test]
+    fn test_decode_openai_stream() {
+        let body = concat!(
+            "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"\"},\"finish_reason\":null}]}\n\n",
+            "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hello\"},\"finish_reason\":null}]}\n\n",
+            "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"type\":\"function\",\"function\":{\"name\":\"get_weather\",\"arguments\":\"\"}}]},\"finish_reason\":null}]}\n\n",
+            "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"{\\\"location\\\":\\\"Paris\\\"}\"}}]},\"finish_reason\":null}]}\n\n",
+            "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"tool_calls\"}]}\n\n",
+            "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"choices\":[],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":9,\"total_tokens\":21}}\n\n",
+            "data: [DONE]\n\n",
+        );
+
+        assert_eq!(decode_all(ClientLlm::OpenAI, body), vec![
+            StreamEvent::MessageStart { id: "chatcmpl-1".to_string(), model: String::new() },
+            StreamEvent::TextDelta { index: 0, text: "Hello".to_string() },
+            StreamEvent::ToolCallDelta { index: 0, id: Some("call_1".to_string()), name: Some("get_weather".to_string()), arguments: String::new() },
+            StreamEvent::ToolCallDelta { index: 0, id: None, name: None, arguments: "{\"location\":\"Paris\"}".to_string() },
+            StreamEvent::ToolCallComplete(ToolResponse {
+                id: "call_1".to_string(),
+                name: "get_weather".to_string(),
+                input: serde_json::json!({"location": "Paris"}),
+            }),
+            StreamEvent::Usage(CommonUsage { input_tokens: 12, output_tokens: 9, ..Default::default() }),
+            StreamEvent::MessageStop { stop_reason: Some("tool_calls".to_string()), stop_sequence: None },
+        ]);
+    }
+
+    #[test]
+    fn test_parse_openai_stream_chunk() {
+        let chunk = OpenAIStreamChunk::parse(r#"{"id":"chatcmpl-1","object":"chat.completion.chunk","created":1720000000,"model":"gpt-4o","system_fingerprint":"fp_44709d6fcb","choices":[{"index":0,"delta":{"tool_calls":[{"index":1,"id":"call_2","type":"function","function":{"name":"get_time","arguments":""}}]},"finish_reason":null}]}"#)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(chunk.model, "gpt-4o");
+        assert_eq!(chunk.system_fingerprint.as_deref(), Some("fp_44709d6fcb"));
+        assert_eq!(chunk.choices[0].delta.tool_calls, Some(vec![DeltaToolCall {
+            index: 1,
+            id: Some("call_2".to_string()),
+            call_type: Some("function".to_string()),
+            function: DeltaFunction { name: Some("get_time".to_string()), arguments: Some(String::new()) },
+        }]));
+        assert_eq!(chunk.choices[0].finish_reason, None);
+        assert!(chunk.usage.is_none());
+        assert_eq!(OpenAIStreamChunk::parse("[DONE]").unwrap(), None);
+    }
+
+    #[test]
+    fn test_response_collector_anthropic() {
+        let events = [
+            StreamEvent::MessageStart { id: "msg_1".to_string(), model: "claude-3-haiku-20240307".to_string() },
+            StreamEvent::TextDelta { index: 0, text: "Let me ".to_string() },
+            StreamEvent::TextDelta { index: 0, text: "check.".to_string() },
+            StreamEvent::ToolCallDelta { index: 1, id: Some("toolu_1".to_string()), name: Some("get_weather".to_string()), arguments: String::new() },
+            StreamEvent::ToolCallDelta { index: 1, id: None, name: None, arguments: "{\"location\":\"Paris\"}".to_string() },
+            StreamEvent::ToolCallComplete(ToolResponse {
+                id: "toolu_1".to_string(),
+                name: "get_weather".to_string(),
+                input: serde_json::json!({"location": "Paris"}),
+            }),
+            StreamEvent::Usage(CommonUsage { input_tokens: 25, output_tokens: 42, ..Default::default() }),
+            StreamEvent::MessageStop { stop_reason: Some("tool_use".to_string()), stop_sequence: None },
+        ];
+        let mut collector = ResponseCollector::new(&ClientLlm::Anthropic).unwrap();
+        events.iter().for_each(|event| collector.push(event));
+
+        let response = collector.finish();
+
+        assert_eq!(response.id(), "msg_1");
+        assert_eq!(response.model(), "claude-3-haiku-20240307");
+        assert_eq!(response.first_message(), "Let me check.");
+        assert_eq!(response.stop_reason(), "tool_use");
+        assert_eq!(response.usage(), CommonUsage { input_tokens: 25, output_tokens: 42, ..Default::default() });
+        assert_eq!(response.tools(), Some(vec![ToolResponse {
+            id: "toolu_1".to_string(),
+            name: "get_weather".to_string(),
+            input: serde_json::json!({"location": "Paris"}),
+        }]));
+    }
+
+    #[test]
+    fn test_response_collector_openai_tool_calls_only() {
+        let events = [
+            StreamEvent::MessageStart { id: "chatcmpl-1".to_string(), model: "gpt-4o".to_string() },
+            StreamEvent::ToolCallDelta { index: 0, id: Some("call_1".to_string()), name: Some("get_weather".to_string()), arguments: "{}".to_string() },
+            StreamEvent::ToolCallComplete(ToolResponse {
+                id: "call_1".to_string(),
+                name: "get_weather".to_string(),
+                input: serde_json::json!({}),
+            }),
+            StreamEvent::Usage(CommonUsage { input_tokens: 12, output_tokens: 9, ..Default::default() }),
+            StreamEvent::MessageStop { stop_reason: Some("tool_calls".to_string()), stop_sequence: None },
+        ];
+        let mut collector = ResponseCollector::new(&ClientLlm::OpenAI).unwrap();
+        events.iter().for_each(|event| collector.push(event));
+
+        let ResponseMessage::OpenAI(response) = collector.finish() else {
+            panic!("expected an OpenAI response");
+        };
+
+        assert_eq!(response.choices.len(), 1);
+        assert_eq!(response.choices[0].message.content, None);
+        assert_eq!(response.choices[0].finish_reason.as_deref(), Some("tool_calls"));
+        let tool_calls = response.choices[0].message.tool_calls.as_ref().unwrap();
+        assert_eq!(tool_calls[0].function.arguments, "{}");
+        assert_eq!(response.usage.total_tokens, 21);
+    }
+
+    #[test]
+    fn test_tool_call_accumulator() {
+        let mut accumulator = ToolCallAccumulator::new();
+        let delta = |index: usize, id: Option<&str>, name: Option<&str>, arguments: &str| StreamEvent::ToolCallDelta {
+            index,
+            id: id.map(str::to_string),
+            name: name.map(str::to_string),
+            arguments: arguments.to_string(),
+        };
+
+        assert_eq!(accumulator.push(&delta(0, Some("call_1"), Some("get_weather"), "{\"loc")), None);
+        assert_eq!(accumulator.push(&delta(1, Some("call_2"), Some("get_time"), "")), None);
+        assert_eq!(accumulator.push(&StreamEvent::TextDelta { index: 0, text: "{}".to_string() }), None);
+        assert_eq!(accumulator.push(&delta(0, None, None, "ation\": \"Paris\"}")), Some(ToolResponse {
+            id: "call_1".to_string(),
+            name: "get_weather".to_string(),
+            input: serde_json::json!({"location": "Paris"}),
+        }));
+        assert_eq!(accumulator.finish().unwrap(), vec![ToolResponse {
+            id: "call_2".to_string(),
+            name: "get_time".to_string(),
+            input: serde_json::json!({}),
+        }]);
+
+        accumulator.push(&delta(0, Some("call_3"), Some("get_weather"), "{\"location\""));
+        assert!(matches!(accumulator.finish(), Err(ApiError::ResponseParseError(_))));
+    }
+
+    #[test]
+    fn test_decode_anthropic_stop_sequence() {
+        let body = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"role\":\"assistant\",\"content\":[],\"usage\":{\"input_tokens\":25,\"output_tokens\":1}}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"1, 2, 3\"}}\n\n",
+            "event: message_delta\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"stop_sequence\",\"stop_sequence\":\"4\"},\"usage\":{\"output_tokens\":8}}\n\n",
+            "event: message_stop\n",
+            "data: {\"type\":\"message_stop\"}\n\n",
+        );
+
+        let events = decode_all(ClientLlm::Anthropic, body);
+        assert_eq!(events.last(), Some(&StreamEvent::MessageStop {
+            stop_reason: Some("stop_sequence".to_string()),
+            stop_sequence: Some("4".to_string()),
+        }));
+
+        let mut collector = ResponseCollector::new(&ClientLlm::Anthropic).unwrap();
+        events.iter().for_each(|event| collector.push(event));
+        assert_eq!(collector.finish().matched_stop_sequence(), Some("4"));
+    }
+
+    #[test]
+    fn test_decode_truncated_tool_call() {
+        let body = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"role\":\"assistant\",\"content\":[],\"usage\":{\"input_tokens\":25,\"output_tokens\":1}}}\n\n",
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_1\",\"name\":\"get_weather\",\"input\":{}}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"location\\\": \\\"Par\"}}\n\n",
+            "event: message_delta\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"max_tokens\",\"stop_sequence\":null},\"usage\":{\"output_tokens\":10}}\n\n",
+            "event: message_stop\n",
+            "data: {\"type\":\"message_stop\"}\n\n",
+        );
+
+        let events = decode_all(ClientLlm::Anthropic, body);
+
+        assert_eq!(&events[events.len() - 3..], &[
+            StreamEvent::Usage(CommonUsage { input_tokens: 25, output_tokens: 10, ..Default::default() }),
+            StreamEvent::ToolCallIncomplete {
+                index: 0,
+                id: "toolu_1".to_string(),
+                name: "get_weather".to_string(),
+                arguments: "{\"location\": \"Par".to_string(),
+            },
+            StreamEvent::MessageStop { stop_reason: Some("max_tokens".to_string()), stop_sequence: None },
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_sentence_stream() {
+        let delta = |text: &str| Ok(StreamEvent::TextDelta { index: 0, text: text.to_string() });
+        let events: EventStream = Box::pin(futures_util::stream::iter(vec![
+            Ok(StreamEvent::MessageStart { id: "msg_1".to_string(), model: "claude".to_string() }),
+            delta("Hello there. Dr"),
+            delta(". Smith is "),
+            delta("here! How are"),
+            delta(" you"),
+            Ok(StreamEvent::MessageStop { stop_reason: Some("end_turn".to_string()), stop_sequence: None }),
+        ]));
+
+        let sentences: Vec<String> = sentence_stream(events).map(Result::unwrap).collect().await;
+
+        assert_eq!(sentences, vec!["Hello there.", "Dr. Smith is here!", "How are you"]);
+    }
+
+    #[test]
+    fn test_unsupported_provider() {
+        assert!(matches!(EventDecoder::new(&ClientLlm::Gemini), Err(ApiError::InvalidUsage(_))));
+    }
+}
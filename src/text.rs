@@ -0,0 +1,282 @@
+//! Text utilities for fitting content into a model's context window and for segmenting
+//! generated text into sentences.
+//!
+//! Token counts are estimated with the common heuristic of roughly four characters per token,
+//! which is close enough for budgeting without shipping a provider-specific tokenizer.
+
+/// Context window used for models missing from the table below.
+const DEFAULT_CONTEXT_WINDOW: usize = 8_192;
+
+/// Context window sizes in tokens, matched against the model name by the longest prefix.
+const CONTEXT_WINDOWS: &[(&str, usize)] = &[
+    ("claude-", 200_000),
+    ("gpt-4.1", 1_047_576),
+    ("gpt-4.5", 128_000),
+    ("gpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4-32k", 32_768),
+    ("gpt-4", 8_192),
+    ("gpt-3.5-turbo", 16_385),
+    ("o1-mini", 128_000),
+    ("o1", 200_000),
+    ("o3", 200_000),
+    ("gemini-1.5-pro", 2_097_152),
+    ("gemini-", 1_048_576),
+];
+
+/// Maximum output tokens per request, matched against the model name by the longest prefix like
+/// `CONTEXT_WINDOWS`. Models missing from the table are not checked.
+const MAX_OUTPUT_TOKENS: &[(&str, u32)] = &[
+    ("claude-3-5-", 8_192),
+    ("claude-3-7-", 64_000),
+    ("claude-3-", 4_096),
+    ("claude-sonnet-4", 64_000),
+    ("claude-opus-4", 32_000),
+    ("gpt-4.1", 32_768),
+    ("gpt-4.5", 16_384),
+    ("gpt-4o", 16_384),
+    ("gpt-4-turbo", 4_096),
+    ("gpt-4-32k", 32_768),
+    ("gpt-4", 8_192),
+    ("gpt-3.5-turbo", 4_096),
+    ("o1-mini", 65_536),
+    ("o1", 100_000),
+    ("o3", 100_000),
+    ("gemini-2.5", 65_536),
+    ("gemini-", 8_192),
+];
+
+/// Estimates the number of tokens in `text`.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Returns the context window of `model` in tokens, falling back to a conservative default for
+/// unknown models.
+pub fn context_window(model: &str) -> usize {
+    longest_prefix_match(CONTEXT_WINDOWS, model).unwrap_or(DEFAULT_CONTEXT_WINDOW)
+}
+
+/// Returns the maximum number of output tokens `model` accepts per request, or `None` for
+/// models missing from the table.
+pub fn max_output_tokens(model: &str) -> Option<u32> {
+    longest_prefix_match(MAX_OUTPUT_TOKENS, model)
+}
+
+/// Returns the value of the longest prefix of `model` in `table`.
+fn longest_prefix_match<T: Copy>(table: &[(&str, T)], model: &str) -> Option<T> {
+    table.iter()
+        .filter(|(prefix, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, value)| *value)
+}
+
+/// Splits `text` into chunks that each fit in the context window of `model`, leaving
+/// `overhead_tokens` free for the prompt and response.
+///
+/// Chunks break on paragraph boundaries where possible, then on sentence boundaries, and only
+/// split inside a sentence when a single sentence is larger than the budget. Concatenating the
+/// chunks yields the original text.
+pub fn chunk_text(text: &str, model: &str, overhead_tokens: usize) -> Vec<String> {
+    let budget = context_window(model).saturating_sub(overhead_tokens).max(1);
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for segment in segments(text, budget) {
+        if !current.is_empty() && estimate_tokens(&current) + estimate_tokens(segment) > budget {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(segment);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Breaks `text` into the largest paragraph, sentence or character-run segments that each fit
+/// within `budget` tokens.
+fn segments(text: &str, budget: usize) -> Vec<&str> {
+    let mut segments = Vec::new();
+    for paragraph in text.split_inclusive("\n\n") {
+        if estimate_tokens(paragraph) <= budget {
+            segments.push(paragraph);
+            continue;
+        }
+        for sentence in paragraph.split_inclusive(['.', '!', '?']) {
+            if estimate_tokens(sentence) <= budget {
+                segments.push(sentence);
+            } else {
+                segments.extend(split_chars(sentence, budget * 4));
+            }
+        }
+    }
+    segments
+}
+
+/// Splits `text` into runs of at most `max_chars` characters, respecting char boundaries.
+fn split_chars(text: &str, max_chars: usize) -> Vec<&str> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for (count, (index, _)) in text.char_indices().enumerate() {
+        if count > 0 && count % max_chars == 0 {
+            runs.push(&text[start..index]);
+            start = index;
+        }
+    }
+    runs.push(&text[start..]);
+    runs
+}
+
+/// Words that end in a period without ending the sentence.
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "vs", "etc", "e.g", "i.e", "inc", "ltd",
+    "co", "corp", "no", "fig", "approx", "dept", "est", "jan", "feb", "mar", "apr", "jun", "jul",
+    "aug", "sep", "sept", "oct", "nov", "dec",
+];
+
+/// Buffers streamed text deltas and yields complete sentences, e.g. for text-to-speech.
+///
+/// A sentence ends at `.`, `!` or `?` (optionally followed by closing quotes or brackets) when
+/// followed by whitespace. Common abbreviations such as "Dr." or "e.g." and single-letter
+/// initials do not end a sentence.
+#[derive(Debug, Default)]
+pub struct SentenceBuffer {
+    buffer: String,
+}
+
+impl SentenceBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a text delta, returning any sentences it completed.
+    pub fn push(&mut self, delta: &str) -> Vec<String> {
+        self.buffer.push_str(delta);
+        let mut sentences = Vec::new();
+        while let Some(end) = self.find_boundary() {
+            let sentence = self.buffer[..end].trim().to_string();
+            self.buffer = self.buffer[end..].trim_start().to_string();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+        }
+        sentences
+    }
+
+    /// Returns the remaining buffered text once the stream has ended.
+    pub fn finish(&mut self) -> Option<String> {
+        let remainder = std::mem::take(&mut self.buffer).trim().to_string();
+        if remainder.is_empty() { None } else { Some(remainder) }
+    }
+
+    /// Returns the byte offset just past the first complete sentence in the buffer.
+    fn find_boundary(&self) -> Option<usize> {
+        let chars: Vec<(usize, char)> = self.buffer.char_indices().collect();
+        for (i, &(offset, c)) in chars.iter().enumerate() {
+            if !matches!(c, '.' | '!' | '?') {
+                continue;
+            }
+            let mut next = i + 1;
+            while next < chars.len() && matches!(chars[next].1, '.' | '!' | '?' | '"' | '\'' | ')' | ']' | '”' | '’') {
+                next += 1;
+            }
+            // The boundary can't be confirmed until the character after the punctuation arrives.
+            let (end, following) = *chars.get(next)?;
+            if !following.is_whitespace() {
+                continue;
+            }
+            if c == '.' && ends_with_abbreviation(&self.buffer[..offset]) {
+                continue;
+            }
+            return Some(end);
+        }
+        None
+    }
+}
+
+/// Returns `true` when the last word of `text` is an abbreviation or a single-letter initial.
+fn ends_with_abbreviation(text: &str) -> bool {
+    let word = text.rsplit(char::is_whitespace).next().unwrap_or_default()
+        .trim_start_matches(['"', '\'', '(', '[', '“', '‘']);
+    let is_initial = word.chars().count() == 1 && word.chars().all(char::is_alphabetic);
+    is_initial || ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_window() {
+        assert_eq!(context_window("claude-3-haiku-20240307"), 200_000);
+        assert_eq!(context_window("gpt-4o-2024-05-13"), 128_000);
+        assert_eq!(context_window("gpt-4-0613"), 8_192);
+        assert_eq!(context_window("gpt-4.1-mini"), 1_047_576);
+        assert_eq!(context_window("gpt-4.5-preview"), 128_000);
+        assert_eq!(context_window("some-local-model"), DEFAULT_CONTEXT_WINDOW);
+    }
+
+    #[test]
+    fn test_max_output_tokens() {
+        assert_eq!(max_output_tokens("claude-3-5-sonnet-20240620"), Some(8_192));
+        assert_eq!(max_output_tokens("claude-3-haiku-20240307"), Some(4_096));
+        assert_eq!(max_output_tokens("gpt-4o-mini"), Some(16_384));
+        assert_eq!(max_output_tokens("gpt-4.1-2025-04-14"), Some(32_768));
+        assert_eq!(max_output_tokens("some-local-model"), None);
+    }
+
+    #[test]
+    fn test_chunk_text_fits_budget_and_reassembles() {
+        let paragraph = "The quick brown fox jumps over the lazy dog. It was not amused! Why would it be? ";
+        let text = (0..40)
+            .map(|i| format!("Paragraph {}. {}\n\n", i, paragraph.repeat(i % 4 + 1)))
+            .collect::<String>()
+            + &"A very long sentence without any breaks ".repeat(100);
+        let overhead = 8_000;
+        let budget = context_window("gpt-4") - overhead;
+
+        let chunks = chunk_text(&text, "gpt-4", overhead);
+
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|chunk| estimate_tokens(chunk) <= budget));
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn test_chunk_text_small_text_single_chunk() {
+        let chunks = chunk_text("Hello, world.", "claude-3-haiku-20240307", 1_000);
+
+        assert_eq!(chunks, vec!["Hello, world."]);
+    }
+
+    #[test]
+    fn test_sentence_buffer_across_deltas() {
+        let deltas = ["Hello there", "! Dr. Smith paid $3.50 for the", " tea, e.g. green", " tea. Did he", " like it? ",
+                      "He said \"yes.\" Then J. R. R. Tolkien", " arrived"];
+        let mut buffer = SentenceBuffer::new();
+
+        let mut sentences: Vec<String> = deltas.iter()
+            .flat_map(|delta| buffer.push(delta))
+            .collect();
+        sentences.extend(buffer.finish());
+
+        assert_eq!(sentences, vec![
+            "Hello there!",
+            "Dr. Smith paid $3.50 for the tea, e.g. green tea.",
+            "Did he like it?",
+            "He said \"yes.\"",
+            "Then J. R. R. Tolkien arrived",
+        ]);
+    }
+
+    #[test]
+    fn test_sentence_buffer_waits_for_following_character() {
+        let mut buffer = SentenceBuffer::new();
+
+        assert!(buffer.push("It ended.").is_empty());
+        assert_eq!(buffer.push(" Next"), vec!["It ended."]);
+        assert_eq!(buffer.finish(), Some("Next".to_string()));
+        assert_eq!(buffer.finish(), None);
+    }
+}
@@ -1,7 +1,10 @@
+use log::warn;
+use serde::Deserialize;
 use serde_json::{json, Map, Value};
 use std::collections::HashMap;
-
-
+use std::fs;
+use std::path::Path;
+use crate::error::ApiError;
 
 #[derive(Debug, Clone)]
 pub struct Tool {
@@ -16,6 +19,7 @@ pub struct ToolParameter {
     description: String,
     required: bool,
     enum_values: Option<Vec<String>>,
+    default: Option<Value>,
 }
 
 pub struct ToolBuilder {
@@ -24,6 +28,12 @@ pub struct ToolBuilder {
     parameters: HashMap<String, ToolParameter>,
 }
 
+impl Default for ToolBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ToolBuilder {
     
     pub fn new() -> Self {
@@ -58,6 +68,33 @@ impl ToolBuilder {
                 description: description.to_string(),
                 required,
                 enum_values: None,
+                default: None,
+            },
+        );
+        self
+    }
+
+    /// Adds a parameter with a JSON Schema `default`, which hints the model at the value to use
+    /// when it has no better one.
+    ///
+    /// Only optional parameters can carry a default: the model must always supply a required
+    /// parameter, so `build` rejects a required parameter with a default.
+    pub fn add_parameter_with_default(
+        mut self,
+        name: &str,
+        parameter_type: &str,
+        description: &str,
+        required: bool,
+        default: Value,
+    ) -> Self {
+        self.parameters.insert(
+            name.to_string(),
+            ToolParameter {
+                parameter_type: parameter_type.to_string(),
+                description: description.to_string(),
+                required,
+                enum_values: None,
+                default: Some(default),
             },
         );
         self
@@ -77,6 +114,7 @@ impl ToolBuilder {
                 description: description.to_string(),
                 required,
                 enum_values: Some(enum_values),
+                default: None,
             },
         );
         self
@@ -85,6 +123,9 @@ impl ToolBuilder {
     pub fn build(self) -> Result<Tool, String> {
         let name = self.name.ok_or("Tool name is required")?;
         let description = self.description.ok_or("Tool description is required")?;
+        if let Some((name, _)) = self.parameters.iter().find(|(_, param)| param.required && param.default.is_some()) {
+            return Err(format!("Required parameter '{}' cannot have a default", name));
+        }
 
         Ok(Tool {
             name,
@@ -94,11 +135,108 @@ impl ToolBuilder {
     }
 }
 
+/// The JSON form of a tool definition accepted by `Tool::from_config`.
+#[derive(Deserialize)]
+struct ToolConfig {
+    name: String,
+    description: String,
+    #[serde(default)]
+    parameters: HashMap<String, ToolParameterConfig>,
+}
+
+#[derive(Deserialize)]
+struct ToolParameterConfig {
+    #[serde(rename = "type", default = "default_parameter_type")]
+    parameter_type: String,
+    description: String,
+    #[serde(default)]
+    required: bool,
+    #[serde(rename = "enum")]
+    enum_values: Option<Vec<String>>,
+    default: Option<Value>,
+}
+
+fn default_parameter_type() -> String {
+    "string".to_string()
+}
+
 impl Tool {
     pub fn builder() -> ToolBuilder {
         ToolBuilder::new()
     }
 
+    /// Builds a tool from a JSON definition of the form:
+    ///
+    /// ```json
+    /// {
+    ///   "name": "get_weather",
+    ///   "description": "Get the current weather in a given location",
+    ///   "parameters": {
+    ///     "location": {"type": "string", "description": "The city and state", "required": true},
+    ///     "unit": {"description": "The temperature unit", "enum": ["celsius", "fahrenheit"]}
+    ///   }
+    /// }
+    /// ```
+    ///
+    /// A parameter's `type` defaults to `"string"` and `required` defaults to `false`. Optional
+    /// parameters may also carry a `default` value; a required parameter with a `default` is
+    /// rejected with `ApiError::InvalidUsage`, as `ToolBuilder::build` does.
+    pub fn from_config(config: &Value) -> Result<Tool, ApiError> {
+        let config = ToolConfig::deserialize(config)?;
+        if let Some((name, _)) = config.parameters.iter().find(|(_, param)| param.required && param.default.is_some()) {
+            return Err(ApiError::InvalidUsage(format!("Required parameter '{}' cannot have a default", name)));
+        }
+        Ok(Tool {
+            name: config.name,
+            description: config.description,
+            parameters: config.parameters.into_iter()
+                .map(|(name, param)| (name, ToolParameter {
+                    parameter_type: param.parameter_type,
+                    description: param.description,
+                    required: param.required,
+                    enum_values: param.enum_values,
+                    default: param.default,
+                }))
+                .collect(),
+        })
+    }
+
+    /// Loads every `.json` tool definition (see `from_config`) in `dir`, in file name order.
+    ///
+    /// When `skip_invalid` is `true`, files that can't be parsed or aren't valid tools are logged
+    /// and skipped; otherwise the first invalid file fails the whole load with
+    /// `ApiError::InvalidUsage`.
+    pub fn load_dir(dir: impl AsRef<Path>, skip_invalid: bool) -> Result<Vec<Tool>, ApiError> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut tools = Vec::new();
+        for path in paths {
+            let loaded = fs::read_to_string(&path)
+                .map_err(ApiError::from)
+                .and_then(|contents| Ok(serde_json::from_str::<Value>(&contents)?))
+                .and_then(|config| Tool::from_config(&config));
+            match loaded {
+                Ok(tool) => tools.push(tool),
+                Err(e) if skip_invalid => warn!("Skipping invalid tool definition {}: {}", path.display(), e),
+                Err(e) => return Err(ApiError::InvalidUsage(
+                    format!("Invalid tool definition {}: {}", path.display(), e))),
+            }
+        }
+        Ok(tools)
+    }
+
+    /// Returns the name the model uses to call this tool.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn to_anthropic_format(&self) -> Value {
         let mut properties = serde_json::Map::new();
         let mut required = Vec::new();
@@ -136,6 +274,52 @@ impl Tool {
         })
     }
 
+    pub fn to_gemini_format(&self) -> Value {
+        let mut properties = serde_json::Map::new();
+        let mut required = Vec::new();
+
+        self.process_tool_input(&mut properties, &mut required);
+
+        json!({
+            "name": self.name,
+            "description": self.description,
+            "parameters": {
+                "type": "object",
+                "properties": properties,
+                "required": required
+            }
+        })
+    }
+
+    /// Formats the tool for an OpenAI Realtime session, which uses a flat function definition.
+    pub fn to_openai_realtime_format(&self) -> Value {
+        let mut tool = self.to_gemini_format();
+        tool["type"] = json!("function");
+        tool
+    }
+
+    /// Formats the tool as an AWS Bedrock Converse `toolSpec`.
+    pub fn to_bedrock_format(&self) -> Value {
+        let mut properties = serde_json::Map::new();
+        let mut required = Vec::new();
+
+        self.process_tool_input(&mut properties, &mut required);
+
+        json!({
+            "toolSpec": {
+                "name": self.name,
+                "description": self.description,
+                "inputSchema": {
+                    "json": {
+                        "type": "object",
+                        "properties": properties,
+                        "required": required
+                    }
+                }
+            }
+        })
+    }
+
     fn process_tool_input(&self, properties: &mut Map<String, Value>, required: &mut Vec<Value>) {
         for (name, param) in &self.parameters {
             let mut property = serde_json::Map::new();
@@ -160,6 +344,10 @@ impl Tool {
                 );
             }
 
+            if let Some(default) = &param.default {
+                property.insert("default".to_string(), default.clone());
+            }
+
             properties.insert(name.clone(), Value::Object(property));
 
             if param.required {
@@ -317,4 +505,141 @@ mod tests {
         expected_required.sort();
         assert_eq!(actual_required, expected_required);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_from_config() {
+        let tool = Tool::from_config(&json!({
+            "name": "get_weather",
+            "description": "Get the current weather in a given location",
+            "parameters": {
+                "location": {"type": "string", "description": "The city and state", "required": true},
+                "unit": {"description": "The temperature unit", "enum": ["celsius", "fahrenheit"]}
+            }
+        })).expect("Failed to load tool");
+
+        assert_eq!(tool.name, "get_weather");
+        let location_param = tool.parameters.get("location").expect("Location parameter not found");
+        assert!(location_param.required);
+        let unit_param = tool.parameters.get("unit").expect("Unit parameter not found");
+        assert_eq!(unit_param.parameter_type, "string");
+        assert!(!unit_param.required);
+        assert_eq!(unit_param.enum_values, Some(vec!["celsius".to_string(), "fahrenheit".to_string()]));
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("weather.json"), json!({
+            "name": "get_weather",
+            "description": "Get the current weather",
+            "parameters": {"location": {"description": "The city", "required": true}}
+        }).to_string()).unwrap();
+        fs::write(dir.path().join("search.json"), json!({
+            "name": "search",
+            "description": "Search the web"
+        }).to_string()).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a tool").unwrap();
+
+        let tools = Tool::load_dir(dir.path(), false).unwrap();
+
+        let names: Vec<&str> = tools.iter().map(|tool| tool.name()).collect();
+        assert_eq!(names, vec!["search", "get_weather"]);
+    }
+
+    #[test]
+    fn test_load_dir_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("search.json"), json!({
+            "name": "search",
+            "description": "Search the web"
+        }).to_string()).unwrap();
+        fs::write(dir.path().join("broken.json"), "{\"name\": ").unwrap();
+
+        assert!(matches!(Tool::load_dir(dir.path(), false), Err(ApiError::InvalidUsage(_))));
+        assert_eq!(Tool::load_dir(dir.path(), true).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parameter_default_in_schemas() {
+        let tool = Tool::builder()
+            .name("search")
+            .description("Search the web")
+            .add_parameter("query", "string", "The search query", true)
+            .add_parameter_with_default("limit", "integer", "Maximum number of results", false, json!(10))
+            .build()
+            .expect("Failed to build tool");
+
+        let anthropic_limit = &tool.to_anthropic_format()["input_schema"]["properties"]["limit"];
+        assert_eq!(anthropic_limit["default"], 10);
+        let openai_limit = &tool.to_openai_format()["function"]["parameters"]["properties"]["limit"];
+        assert_eq!(openai_limit["default"], 10);
+        let openai_query = &tool.to_openai_format()["function"]["parameters"]["properties"]["query"];
+        assert!(openai_query.get("default").is_none());
+    }
+
+    #[test]
+    fn test_required_parameter_with_default_rejected() {
+        let result = Tool::builder()
+            .name("search")
+            .description("Search the web")
+            .add_parameter_with_default("limit", "integer", "Maximum number of results", true, json!(10))
+            .build();
+
+        assert_eq!(result.unwrap_err(), "Required parameter 'limit' cannot have a default");
+    }
+
+    #[test]
+    fn test_from_config_required_parameter_with_default_rejected() {
+        let config = json!({
+            "name": "search",
+            "description": "Search the web",
+            "parameters": {
+                "limit": {"type": "integer", "description": "Maximum number of results", "required": true, "default": 10}
+            }
+        });
+
+        match Tool::from_config(&config) {
+            Err(ApiError::InvalidUsage(message)) => {
+                assert_eq!(message, "Required parameter 'limit' cannot have a default");
+            }
+            other => panic!("Expected InvalidUsage, got {:?}", other.map(|tool| tool.name)),
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("search.json"), config.to_string()).unwrap();
+        fs::write(dir.path().join("weather.json"), json!({
+            "name": "get_weather",
+            "description": "Get the current weather"
+        }).to_string()).unwrap();
+
+        assert!(matches!(Tool::load_dir(dir.path(), false), Err(ApiError::InvalidUsage(_))));
+        let tools = Tool::load_dir(dir.path(), true).unwrap();
+        assert_eq!(tools.iter().map(|tool| tool.name()).collect::<Vec<_>>(), vec!["get_weather"]);
+    }
+
+    #[test]
+    fn test_to_gemini_format() {
+        let tool = Tool::builder()
+            .name("get_weather")
+            .description("Get the current weather in a given location")
+            .add_parameter("location", "string", "The city and state, e.g. San Francisco, CA", true)
+            .build()
+            .expect("Failed to build tool");
+
+        let expected = json!({
+            "name": "get_weather",
+            "description": "Get the current weather in a given location",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "location": {
+                        "type": "string",
+                        "description": "The city and state, e.g. San Francisco, CA"
+                    }
+                },
+                "required": ["location"]
+            }
+        });
+        assert_eq!(tool.to_gemini_format(), expected);
+    }
+}
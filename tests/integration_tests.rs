@@ -12,7 +12,7 @@ mod tests {
         let api_key = std::env::var("ANTHROPIC_API_KEY")
             .expect("ANTHROPIC_API_KEY must be set.");
         let client_type = ClientLlm::Anthropic;
-        let mut client = LlmClient::new(client_type, api_key);
+        let client = LlmClient::new(client_type, api_key);
 
         let response = client
             .request()
@@ -44,7 +44,7 @@ mod tests {
         let api_key = std::env::var("OPENAI_API_KEY")
             .expect("ANTHROPIC_API_KEY must be set.");
         let client_type = ClientLlm::OpenAI;
-        let mut client = LlmClient::new(client_type, api_key);
+        let client = LlmClient::new(client_type, api_key);
 
         let response = client
             .request()
@@ -74,7 +74,7 @@ mod tests {
     async fn test_invalid_api_key() {
         let api_key = "i am invalid".to_string();
         let client_type = ClientLlm::Anthropic;
-        let mut client = LlmClient::new(client_type, api_key);
+        let client = LlmClient::new(client_type, api_key);
 
         let response = client
             .request()
@@ -109,8 +109,8 @@ mod tests {
             let client = shared_client.clone();
             let handle = thread::spawn(move || {
                 // Use the shared LlmClient within each thread
-                let mut client_guard = client.lock().unwrap();
-                let request_builder = client_guard
+                let client_guard = client.lock().unwrap();
+                let _request_builder = client_guard
                     .request()
                     .model("claude-3-haiku-20240307")
                     .user_message("Hello, Claude!")
@@ -136,7 +136,7 @@ mod tests {
         let api_key = std::env::var("ANTHROPIC_API_KEY")
             .expect("ANTHROPIC_API_KEY must be set.");
         let client_type = ClientLlm::Anthropic;
-        let mut client = LlmClient::new(client_type, api_key);
+        let client = LlmClient::new(client_type, api_key);
 
         let tool = Tool::builder()
             .name("get_weather")
@@ -176,7 +176,7 @@ mod tests {
         let api_key = std::env::var("OPENAI_API_KEY")
             .expect("OPENAI_API_KEY must be set.");
         let client_type = ClientLlm::OpenAI;
-        let mut client = LlmClient::new(client_type, api_key);
+        let client = LlmClient::new(client_type, api_key);
 
         let tool = Tool::builder()
             .name("get_weather")